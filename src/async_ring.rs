@@ -0,0 +1,333 @@
+//! Асинхронное изъятие элементов и ожидание первой непустой из нескольких очередей.
+
+use core::cell::{Cell, RefCell};
+use core::future::poll_fn;
+use core::task::{Poll, Waker};
+
+use crate::FrodoRing;
+
+/// Очередь для использования в пределах одного асинхронного исполнителя: `pick()` ожидает появления элемента.
+///
+/// `push()` будит все ожидающие задачи-потребители, а изъятие элемента - задачи-производители,
+/// ожидающие места, так что никому не нужно опрашивать очередь в цикле.
+pub struct FrodoAsyncRing<T, const N: usize> {
+    ring: RefCell<FrodoRing<T, N>>,
+    /// Потребители, ожидающие элемента (`pick()`, `not_empty()`, `FrodoSelect`).
+    consumers: WakerSet,
+    /// Производители, ожидающие свободного места (`not_full()`).
+    producers: WakerSet,
+}
+
+impl<T, const N: usize> Default for FrodoAsyncRing<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: RefCell::new(FrodoRing::new()),
+            consumers: WakerSet::new(),
+            producers: WakerSet::new(),
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoAsyncRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.borrow().len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.borrow().is_empty()
+    }
+
+    /// Кладёт элемент в очередь и будит ожидающих потребителей.
+    ///
+    /// Будятся все ожидающие: не получившие элемента задачи снова зарегистрируются при следующем опросе.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        self.ring.borrow_mut().push(item)?;
+        self.consumers.wake_all();
+        Ok(())
+    }
+
+    /// Изымает первый элемент очереди, не ожидая, и будит ожидающих места производителей.
+    pub fn try_pick(&self) -> Option<T> {
+        let item = self.ring.borrow_mut().pick()?;
+        self.producers.wake_all();
+        Some(item)
+    }
+
+    /// Сообщает, заполнена ли очередь; см. `FrodoRing::is_full`.
+    pub fn is_full(&self) -> bool {
+        self.ring.borrow().is_full()
+    }
+
+    /// Ожидает, пока в очереди появится элемент, не изымая его.
+    pub async fn not_empty(&self) {
+        poll_fn(|cx| {
+            if self.is_empty() {
+                self.consumers.register(cx.waker());
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+
+    /// Ожидает, пока в очереди появится свободное место.
+    pub async fn not_full(&self) {
+        poll_fn(|cx| {
+            if self.is_full() {
+                self.producers.register(cx.waker());
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+
+    /// Ожидает и изымает первый элемент очереди.
+    pub async fn pick(&self) -> T {
+        poll_fn(|cx| match self.try_pick() {
+            Some(item) => Poll::Ready(item),
+            None => {
+                self.consumers.register(cx.waker());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+/// Число ожидающих задач одной стороны, которые хранятся без вытеснения.
+const WAITERS: usize = 4;
+
+/// Небольшой набор задач, ожидающих одного события.
+///
+/// Если места в наборе нет, вытесненная задача будится сразу: она опросит очередь заново
+/// и зарегистрируется снова, так что пробуждение не теряется.
+struct WakerSet {
+    slots: [Cell<Option<Waker>>; WAITERS],
+    /// Ячейка, вытесняемая при следующем переполнении.
+    evict: Cell<usize>,
+}
+
+impl WakerSet {
+    const fn new() -> Self {
+        Self {
+            slots: [const { Cell::new(None) }; WAITERS],
+            evict: Cell::new(0),
+        }
+    }
+
+    /// Запоминает задачу, не клонируя `Waker`, если она уже в наборе.
+    fn register(&self, waker: &Waker) {
+        let mut free = None;
+        for slot in &self.slots {
+            match slot.take() {
+                Some(current) if current.will_wake(waker) => {
+                    slot.set(Some(current));
+                    return;
+                }
+                Some(current) => slot.set(Some(current)),
+                None => {
+                    free.get_or_insert(slot);
+                }
+            }
+        }
+
+        if let Some(slot) = free {
+            slot.set(Some(waker.clone()));
+            return;
+        }
+
+        let evict = self.evict.get();
+        self.evict.set((evict + 1) % WAITERS);
+        if let Some(evicted) = self.slots[evict].replace(Some(waker.clone())) {
+            evicted.wake();
+        }
+    }
+
+    /// Будит и забывает все задачи набора.
+    fn wake_all(&self) {
+        for slot in &self.slots {
+            if let Some(waker) = slot.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Ожидание первой непустой из `K` очередей со справедливым обходом.
+///
+/// Хранит позицию, с которой начинается следующий обход, так что постоянно непустая очередь
+/// не заслоняет остальные.
+pub struct FrodoSelect<const K: usize> {
+    next: usize,
+}
+
+impl<const K: usize> Default for FrodoSelect<K> {
+    fn default() -> Self {
+        Self { next: 0 }
+    }
+}
+
+impl<const K: usize> FrodoSelect<K> {
+    /// Создаёт новый выбор.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ожидает элемент в любой из очередей и возвращает его вместе с индексом очереди.
+    pub async fn select<T, const N: usize>(
+        &mut self,
+        rings: [&FrodoAsyncRing<T, N>; K],
+    ) -> (usize, T) {
+        poll_fn(|cx| {
+            for offset in 0..K {
+                let i = (self.next + offset) % K;
+                if let Some(item) = rings[i].try_pick() {
+                    self.next = (i + 1) % K;
+                    return Poll::Ready((i, item));
+                }
+            }
+
+            for ring in rings {
+                ring.consumers.register(cx.waker());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::pin::pin;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use core::task::{Context, Poll, Waker};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use super::*;
+
+    fn poll_once<F: Future>(future: F) -> Poll<F::Output> {
+        let mut cx = Context::from_waker(Waker::noop());
+        pin!(future).poll(&mut cx)
+    }
+
+    /// Задача, запоминающая, что её разбудили.
+    struct Woken(AtomicBool);
+
+    impl Wake for Woken {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn woken() -> (Arc<Woken>, Waker) {
+        let woken = Arc::new(Woken(AtomicBool::new(false)));
+        (woken.clone(), Waker::from(woken))
+    }
+
+    #[test]
+    fn async_pick() {
+        let ring = FrodoAsyncRing::<u8, 2>::new();
+        assert_eq!(poll_once(ring.pick()), Poll::Pending);
+        ring.push(1).unwrap();
+        assert_eq!(poll_once(ring.pick()), Poll::Ready(1));
+    }
+
+    #[test]
+    fn concurrent_consumers() {
+        let a = FrodoAsyncRing::<u8, 2>::new();
+        let b = FrodoAsyncRing::<u8, 2>::new();
+        let mut select = FrodoSelect::<2>::new();
+
+        let mut pick = pin!(a.pick());
+        let mut not_empty = pin!(a.not_empty());
+        let mut selected = pin!(select.select([&a, &b]));
+        let waiters = [woken(), woken(), woken()];
+
+        let mut cx = Context::from_waker(&waiters[0].1);
+        assert_eq!(pick.as_mut().poll(&mut cx), Poll::Pending);
+        let mut cx = Context::from_waker(&waiters[1].1);
+        assert_eq!(not_empty.as_mut().poll(&mut cx), Poll::Pending);
+        let mut cx = Context::from_waker(&waiters[2].1);
+        assert_eq!(selected.as_mut().poll(&mut cx), Poll::Pending);
+
+        a.push(1).unwrap();
+        assert!(
+            waiters
+                .iter()
+                .all(|(woken, _)| woken.0.load(Ordering::Relaxed))
+        );
+        assert_eq!(selected.as_mut().poll(&mut cx), Poll::Ready((0, 1)));
+    }
+
+    #[test]
+    fn concurrent_producers() {
+        let ring = FrodoAsyncRing::<u8, 1>::new();
+        ring.push(1).unwrap();
+
+        let waiters = [woken(), woken(), woken(), woken(), woken()];
+        let mut futures = [(); 5].map(|()| Box::pin(ring.not_full()));
+        for (future, (_, waker)) in futures.iter_mut().zip(&waiters) {
+            let mut cx = Context::from_waker(waker);
+            assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+        }
+
+        // Пятая задача не помещается в набор и вытесняет первую, которая тут же будится
+        let woken_now = waiters
+            .each_ref()
+            .map(|(woken, _)| woken.0.load(Ordering::Relaxed));
+        assert_eq!(woken_now, [true, false, false, false, false]);
+
+        assert_eq!(ring.try_pick(), Some(1));
+        assert!(
+            waiters[1..]
+                .iter()
+                .all(|(woken, _)| woken.0.load(Ordering::Relaxed))
+        );
+    }
+
+    #[test]
+    fn select_fair() {
+        let a = FrodoAsyncRing::<u8, 4>::new();
+        let b = FrodoAsyncRing::<u8, 4>::new();
+        let mut select = FrodoSelect::<2>::new();
+        assert_eq!(poll_once(select.select([&a, &b])), Poll::Pending);
+
+        for i in 0..3 {
+            a.push(i).unwrap();
+        }
+        b.push(10).unwrap();
+
+        assert_eq!(poll_once(select.select([&a, &b])), Poll::Ready((0, 0)));
+        assert_eq!(poll_once(select.select([&a, &b])), Poll::Ready((1, 10)));
+        assert_eq!(poll_once(select.select([&a, &b])), Poll::Ready((0, 1)));
+        assert_eq!(poll_once(select.select([&a, &b])), Poll::Ready((0, 2)));
+    }
+
+    #[test]
+    fn readiness() {
+        let ring = FrodoAsyncRing::<u8, 1>::new();
+        assert_eq!(poll_once(ring.not_empty()), Poll::Pending);
+        assert_eq!(poll_once(ring.not_full()), Poll::Ready(()));
+
+        ring.push(1).unwrap();
+        assert_eq!(poll_once(ring.not_empty()), Poll::Ready(()));
+        assert_eq!(poll_once(ring.not_full()), Poll::Pending);
+        assert_eq!(ring.len(), 1);
+        assert!(ring.is_full());
+
+        ring.try_pick();
+        assert!(!ring.is_full());
+        assert_eq!(poll_once(ring.not_full()), Poll::Ready(()));
+    }
+}
@@ -0,0 +1,267 @@
+//! Очередь с атомарной картой занятости: изъятие из середины параллельно с добавлением.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Очередь с одним производителем и одним потребителем, в которой потребитель может изымать элементы
+/// из середины очереди, пока производитель (например, прерывание) продолжает добавлять элементы.
+///
+/// Занятость ячеек хранится в атомарных флагах: производитель помечает новую ячейку занятой до публикации
+/// нового конца очереди (`Release`), потребитель снимает пометку только с уже опубликованных ячеек.
+/// Пустые ячейки освобождают место для производителя, когда начало очереди проходит через них; сжатия нет.
+///
+/// Начало и конец - свободно переполняющиеся счётчики, поэтому ёмкость `N` должна быть степенью двойки;
+/// другие значения отвергаются при компиляции.
+pub struct FrodoAtomicRing<T, const N: usize> {
+    buffer: UnsafeCell<[MaybeUninit<T>; N]>,
+    occupied: [AtomicBool; N],
+    /// Начало очереди; изменяется только потребителем.
+    head: AtomicUsize,
+    /// Конец очереди; изменяется только производителем.
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for FrodoAtomicRing<T, N> {}
+
+impl<T, const N: usize> Default for FrodoAtomicRing<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for FrodoAtomicRing<T, N> {
+    fn drop(&mut self) {
+        let (_, mut consumer) = self.split();
+        while consumer.poll().is_some() {}
+    }
+}
+
+impl<T, const N: usize> FrodoAtomicRing<T, N> {
+    /// Ёмкость, не являющаяся степенью двойки (в том числе нулевая), отвергается при компиляции.
+    const POWER_OF_TWO: () = assert!(
+        N.is_power_of_two(),
+        "FrodoAtomicRing capacity N must be a power of two"
+    );
+
+    /// Создаёт новую очередь; может использоваться для инициализации `static`.
+    pub const fn new() -> Self {
+        let () = Self::POWER_OF_TWO;
+
+        Self {
+            buffer: UnsafeCell::new([const { MaybeUninit::uninit() }; N]),
+            occupied: [const { AtomicBool::new(false) }; N],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Разделяет очередь на производителя и потребителя.
+    pub fn split(&mut self) -> (FrodoAtomicProducer<'_, T, N>, FrodoAtomicConsumer<'_, T, N>) {
+        (
+            FrodoAtomicProducer { ring: self },
+            FrodoAtomicConsumer { ring: self },
+        )
+    }
+}
+
+/// Сторона производителя `FrodoAtomicRing`.
+pub struct FrodoAtomicProducer<'ring, T, const N: usize> {
+    ring: &'ring FrodoAtomicRing<T, N>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for FrodoAtomicProducer<'_, T, N> {}
+
+impl<T, const N: usize> FrodoAtomicProducer<'_, T, N> {
+    /// Кладёт элемент в конец очереди за `O(1)`; возвращает его обратно, если свободных ячеек за концом нет.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let ring = self.ring;
+        let tail = ring.tail.load(Ordering::Relaxed);
+        let head = ring.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == N {
+            return Err(item);
+        }
+
+        let real_pos = tail % N;
+        unsafe { (*ring.buffer.get())[real_pos].write(item) };
+        ring.occupied[real_pos].store(true, Ordering::Relaxed);
+        ring.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Сторона потребителя `FrodoAtomicRing`.
+pub struct FrodoAtomicConsumer<'ring, T, const N: usize> {
+    ring: &'ring FrodoAtomicRing<T, N>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for FrodoAtomicConsumer<'_, T, N> {}
+
+impl<T, const N: usize> FrodoAtomicConsumer<'_, T, N> {
+    /// Возвращает опубликованное производителем число используемых ячеек.
+    pub fn used(&self) -> usize {
+        let head = self.ring.head.load(Ordering::Relaxed);
+        self.ring.tail.load(Ordering::Acquire).wrapping_sub(head)
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        (0..self.used())
+            .filter(|naive_pos| self.is_occupied(*naive_pos))
+            .count()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.used() == 0
+    }
+
+    fn real_pos(&self, naive_pos: usize) -> usize {
+        self.ring
+            .head
+            .load(Ordering::Relaxed)
+            .wrapping_add(naive_pos)
+            % N
+    }
+
+    fn is_occupied(&self, naive_pos: usize) -> bool {
+        self.ring.occupied[self.real_pos(naive_pos)].load(Ordering::Relaxed)
+    }
+
+    /// Получает элемент по ячейке (наивной позиции от начала очереди).
+    pub fn at(&self, naive_pos: usize) -> Option<&T> {
+        if naive_pos >= self.used() || !self.is_occupied(naive_pos) {
+            return None;
+        }
+
+        Some(unsafe { (*self.ring.buffer.get())[self.real_pos(naive_pos)].assume_init_ref() })
+    }
+
+    /// Получает наивную позицию элемента, отвечающего условию.
+    pub fn position<F: Fn(&T) -> bool>(&self, f: F) -> Option<usize> {
+        (0..self.used()).find(|naive_pos| self.at(*naive_pos).is_some_and(&f))
+    }
+
+    /// Изымает содержимое ячейки по наивной позиции.
+    ///
+    /// Если изъят первый элемент, начало очереди сдвигается через все пустые ячейки, освобождая место производителю.
+    pub fn remove_at(&mut self, naive_pos: usize) -> Option<T> {
+        let used = self.used();
+        if naive_pos >= used || !self.is_occupied(naive_pos) {
+            return None;
+        }
+
+        let ring = self.ring;
+        let real_pos = self.real_pos(naive_pos);
+        let item = unsafe { (*ring.buffer.get())[real_pos].assume_init_read() };
+        ring.occupied[real_pos].store(false, Ordering::Relaxed);
+
+        if naive_pos == 0 {
+            let mut head = ring.head.load(Ordering::Relaxed);
+            let tail = head.wrapping_add(used);
+            while head != tail && !ring.occupied[head % N].load(Ordering::Relaxed) {
+                head = head.wrapping_add(1);
+            }
+            ring.head.store(head, Ordering::Release);
+        }
+
+        Some(item)
+    }
+
+    /// Забирает первый элемент очереди, если он есть.
+    pub fn poll(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.remove_at(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_ring() {
+        let mut ring = FrodoAtomicRing::<u8, 4>::new();
+        let (mut producer, mut consumer) = ring.split();
+
+        for i in 0x1..=0x4 {
+            assert!(producer.push(i).is_ok());
+        }
+        assert_eq!(producer.push(0x5), Err(0x5));
+
+        assert_eq!(consumer.remove_at(1), Some(0x2));
+        assert_eq!(consumer.remove_at(2), Some(0x3));
+        assert_eq!(consumer.len(), 2);
+        assert_eq!(producer.push(0x5), Err(0x5));
+
+        assert_eq!(consumer.poll(), Some(0x1));
+        assert_eq!(consumer.used(), 1);
+        assert!(producer.push(0x5).is_ok());
+        assert_eq!(consumer.position(|el| *el == 0x5), Some(1));
+        assert_eq!(consumer.poll(), Some(0x4));
+        assert_eq!(consumer.poll(), Some(0x5));
+        assert_eq!(consumer.poll(), None);
+    }
+
+    #[test]
+    fn atomic_ring_counter_wrap() {
+        let mut ring = FrodoAtomicRing::<u8, 4>::new();
+        ring.head = AtomicUsize::new(usize::MAX - 1);
+        ring.tail = AtomicUsize::new(usize::MAX - 1);
+        let (mut producer, mut consumer) = ring.split();
+
+        for i in 0x1..=0x4 {
+            assert!(producer.push(i).is_ok());
+        }
+        assert_eq!(consumer.remove_at(2), Some(0x3));
+        assert_eq!(consumer.poll(), Some(0x1));
+        assert_eq!(consumer.poll(), Some(0x2));
+        assert_eq!(consumer.used(), 1);
+        assert!(producer.push(0x5).is_ok());
+        assert_eq!(consumer.at(1), Some(&0x5));
+        assert_eq!(consumer.poll(), Some(0x4));
+        assert_eq!(consumer.poll(), Some(0x5));
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn atomic_ring_threads() {
+        let mut ring = FrodoAtomicRing::<u32, 8>::new();
+        let (mut producer, mut consumer) = ring.split();
+
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..1000 {
+                    let mut item = i;
+                    while let Err(back) = producer.push(item) {
+                        item = back;
+                        std::thread::yield_now();
+                    }
+                }
+            });
+
+            // Нечётные элементы изымаются из середины, чётные - по порядку
+            let mut expected = 0;
+            let mut odd = 0;
+            while expected < 1000 {
+                if let Some(pos) = consumer.position(|el| *el % 2 == 1) {
+                    assert_eq!(consumer.remove_at(pos).map(|el| el % 2), Some(1));
+                    odd += 1;
+                }
+                if consumer.at(0).is_some_and(|el| *el % 2 == 0) {
+                    assert_eq!(consumer.poll(), Some(expected));
+                    expected += 2;
+                }
+            }
+            while odd < 500 {
+                if let Some(el) = consumer.poll() {
+                    assert_eq!(el % 2, 1);
+                    odd += 1;
+                }
+            }
+            assert!(consumer.is_empty());
+        });
+    }
+}
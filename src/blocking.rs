@@ -0,0 +1,121 @@
+//! Очередь с блокирующим потребителем для хостовых потоков.
+
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+use crate::FrodoRing;
+
+/// Очередь, разделяемая между потоками, потребитель которой может ждать элементы, усыпляя поток.
+///
+/// После `close()` новые элементы не принимаются, а ожидание заканчивается, как только очередь опустеет.
+pub struct FrodoBlockingRing<T, const N: usize> {
+    /// Очередь и признак закрытия.
+    state: Mutex<(FrodoRing<T, N>, bool)>,
+    available: Condvar,
+}
+
+impl<T, const N: usize> Default for FrodoBlockingRing<T, N> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new((FrodoRing::new(), false)),
+            available: Condvar::new(),
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoBlockingRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (FrodoRing<T, N>, bool)> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Возвращает число элементов в очереди на момент вызова.
+    pub fn len(&self) -> usize {
+        self.lock().0.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы на момент вызова.
+    pub fn is_empty(&self) -> bool {
+        self.lock().0.is_empty()
+    }
+
+    /// Кладёт элемент в очередь и будит ждущего потребителя.
+    ///
+    /// Возвращает элемент обратно, если очередь заполнена или закрыта.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        let mut state = self.lock();
+        if state.1 {
+            return Err(item);
+        }
+
+        state.0.push(item)?;
+        self.available.notify_one();
+        Ok(())
+    }
+
+    /// Отдаёт первый элемент, не дожидаясь его появления.
+    pub fn pick(&self) -> Option<T> {
+        self.lock().0.pick()
+    }
+
+    /// Отдаёт первый элемент, усыпляя поток, пока очередь пуста.
+    ///
+    /// Возвращает `None`, только если очередь закрыта и пуста.
+    pub fn pick_blocking(&self) -> Option<T> {
+        let mut state = self.lock();
+        loop {
+            if let Some(item) = state.0.pick() {
+                return Some(item);
+            }
+            if state.1 {
+                return None;
+            }
+            state = self
+                .available
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Закрывает очередь: `push()` больше не принимает элементы, а ждущие потребители просыпаются.
+    pub fn close(&self) {
+        self.lock().1 = true;
+        self.available.notify_all();
+    }
+
+    /// Создаёт итератор, выдающий элементы по мере поступления, до закрытия и опустошения очереди.
+    pub fn iter_blocking(&self) -> impl Iterator<Item = T> + '_ {
+        core::iter::from_fn(|| self.pick_blocking())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iter_blocking() {
+        let ring = FrodoBlockingRing::<u32, 4>::new();
+
+        let sum = std::thread::scope(|s| {
+            let consumer = s.spawn(|| ring.iter_blocking().sum::<u32>());
+            for i in 1..=100 {
+                let mut item = i;
+                while let Err(back) = ring.push(item) {
+                    item = back;
+                    std::thread::yield_now();
+                }
+            }
+            ring.close();
+            consumer.join().unwrap()
+        });
+
+        assert_eq!(sum, 5050);
+        assert_eq!(ring.push(1), Err(1));
+    }
+}
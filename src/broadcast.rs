@@ -0,0 +1,259 @@
+//! Широковещательная очередь с несколькими читателями и политиками отставания.
+
+use crate::FrodoRing;
+
+/// Поведение при отставании читателя более чем на `N` элементов.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// `read()` сообщает число пропущенных элементов (`Lagged`) и переходит к старейшему доступному.
+    Error,
+    /// `read()` молча переходит к старейшему доступному элементу.
+    Skip,
+    /// Писатель не может затереть непрочитанный этим читателем элемент: `push()` возвращает его обратно.
+    Block,
+}
+
+/// Читатель отстал и пропустил указанное число элементов.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lagged(pub u64);
+
+impl core::fmt::Display for Lagged {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "reader lagged behind by {} elements", self.0)
+    }
+}
+
+impl core::error::Error for Lagged {}
+
+/// Идентификатор читателя `FrodoBroadcast`.
+///
+/// Помечен идентификатором очереди и поколением места читателя: после `remove_reader()` или в другой очереди
+/// идентификатор не находит читателя, даже если его место занято новым.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReaderId {
+    broadcast: usize,
+    index: usize,
+    generation: u32,
+}
+
+/// Состояние читателя.
+#[derive(Clone, Copy)]
+struct Reader {
+    /// Номер следующего элемента для чтения.
+    cursor: u64,
+    policy: LagPolicy,
+    /// Общее число пропущенных из-за отставания элементов.
+    missed: u64,
+}
+
+/// Очередь, каждый элемент которой получает каждый из до `R` читателей.
+///
+/// Хранятся последние `N` элементов; заполненная очередь затирает старейший элемент, если это не запрещено
+/// читателем с политикой `LagPolicy::Block`. Каждый читатель читает со своей позиции.
+pub struct FrodoBroadcast<T, const N: usize, const R: usize> {
+    ring: FrodoRing<T, N>,
+    /// Номер старейшего хранимого элемента.
+    first_seq: u64,
+    readers: [Option<Reader>; R],
+    /// Поколение каждого места читателя, увеличиваемое при удалении читателя.
+    generations: [u32; R],
+    /// Идентификатор очереди, которым помечаются её читатели.
+    id: usize,
+}
+
+impl<T, const N: usize, const R: usize> Default for FrodoBroadcast<T, N, R> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            first_seq: 0,
+            readers: [None; R],
+            generations: [0; R],
+            id: crate::next_instance_id(),
+        }
+    }
+}
+
+impl<T: Clone, const N: usize, const R: usize> FrodoBroadcast<T, N, R> {
+    /// Создаёт новую очередь без читателей.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Номер, который получит следующий положенный элемент.
+    fn next_seq(&self) -> u64 {
+        self.first_seq + self.ring.len() as u64
+    }
+
+    /// Добавляет читателя, который получит элементы, положенные после этого вызова.
+    ///
+    /// Возвращает `None`, если все `R` мест читателей заняты.
+    pub fn add_reader(&mut self, policy: LagPolicy) -> Option<ReaderId> {
+        let cursor = self.next_seq();
+        let (index, slot) = self
+            .readers
+            .iter_mut()
+            .enumerate()
+            .find(|(_, slot)| slot.is_none())?;
+        *slot = Some(Reader {
+            cursor,
+            policy,
+            missed: 0,
+        });
+        Some(ReaderId {
+            broadcast: self.id,
+            index,
+            generation: self.generations[index],
+        })
+    }
+
+    /// Удаляет читателя, освобождая его место.
+    ///
+    /// Возвращает `false`, если читатель уже удалён или принадлежит другой очереди.
+    pub fn remove_reader(&mut self, id: ReaderId) -> bool {
+        if self.reader(id).is_none() {
+            return false;
+        }
+        self.readers[id.index] = None;
+        self.generations[id.index] = self.generations[id.index].wrapping_add(1);
+        true
+    }
+
+    /// Находит место читателя `id`; `None`, если читатель удалён или принадлежит другой очереди.
+    fn reader(&self, id: ReaderId) -> Option<usize> {
+        (id.broadcast == self.id
+            && self.generations[id.index] == id.generation
+            && self.readers[id.index].is_some())
+        .then_some(id.index)
+    }
+
+    /// Кладёт элемент, при заполненности затирая старейший.
+    ///
+    /// Возвращает элемент обратно, если старейший ещё не прочитан читателем с политикой `LagPolicy::Block`.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.ring.len() == N {
+            let first_seq = self.first_seq;
+            let blocked = self
+                .readers
+                .iter()
+                .flatten()
+                .any(|reader| reader.policy == LagPolicy::Block && reader.cursor <= first_seq);
+            if blocked {
+                return Err(item);
+            }
+
+            self.ring.pick();
+            self.first_seq += 1;
+        }
+
+        self.ring.push(item)
+    }
+
+    /// Читает следующий элемент для читателя `id`.
+    ///
+    /// Если читатель отстал, в зависимости от его политики возвращает `Err(Lagged)` (следующий вызов продолжит
+    /// со старейшего доступного элемента) либо сразу переходит к старейшему доступному.
+    /// Для удалённого или чужого читателя возвращает `Ok(None)`.
+    pub fn read(&mut self, id: ReaderId) -> Result<Option<T>, Lagged> {
+        let first_seq = self.first_seq;
+        let Some(reader) = self
+            .reader(id)
+            .and_then(|index| self.readers[index].as_mut())
+        else {
+            return Ok(None);
+        };
+
+        if reader.cursor < first_seq {
+            let lag = first_seq - reader.cursor;
+            reader.cursor = first_seq;
+            reader.missed += lag;
+            if reader.policy == LagPolicy::Error {
+                return Err(Lagged(lag));
+            }
+        }
+
+        let item = self.ring.get((reader.cursor - first_seq) as usize).cloned();
+        if item.is_some() {
+            reader.cursor += 1;
+        }
+        Ok(item)
+    }
+
+    /// Возвращает число элементов, ещё не прочитанных читателем `id`, включая уже затёртые.
+    ///
+    /// Возвращает `None` для удалённого или чужого читателя.
+    pub fn lag(&self, id: ReaderId) -> Option<u64> {
+        let reader = self.readers[self.reader(id)?]?;
+        Some(self.next_seq() - reader.cursor)
+    }
+
+    /// Возвращает общее число элементов, пропущенных читателем `id` из-за отставания.
+    ///
+    /// Возвращает `None` для удалённого или чужого читателя.
+    pub fn missed(&self, id: ReaderId) -> Option<u64> {
+        Some(self.readers[self.reader(id)?]?.missed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn broadcast_lag() {
+        let mut ring = FrodoBroadcast::<u8, 2, 3>::new();
+        let strict = ring.add_reader(LagPolicy::Error).unwrap();
+        let lossy = ring.add_reader(LagPolicy::Skip).unwrap();
+
+        for i in 1..=4 {
+            assert!(ring.push(i).is_ok());
+        }
+        assert_eq!(ring.lag(strict), Some(4));
+        assert_eq!(ring.read(strict), Err(Lagged(2)));
+        assert_eq!(ring.read(strict), Ok(Some(3)));
+        assert_eq!(ring.read(lossy), Ok(Some(3)));
+        assert_eq!(ring.missed(lossy), Some(2));
+
+        let slow = ring.add_reader(LagPolicy::Block).unwrap();
+        assert!(ring.add_reader(LagPolicy::Skip).is_none());
+        assert!(ring.push(5).is_ok());
+        assert!(ring.push(6).is_ok());
+        assert_eq!(ring.push(7), Err(7));
+        assert_eq!(ring.read(slow), Ok(Some(5)));
+        assert!(ring.push(7).is_ok());
+        assert_eq!(ring.read(slow), Ok(Some(6)));
+        assert_eq!(ring.read(slow), Ok(Some(7)));
+        assert_eq!(ring.read(slow), Ok(None));
+        assert_eq!(ring.missed(slow), Some(0));
+    }
+
+    #[test]
+    fn stale_and_foreign_readers() {
+        let mut ring = FrodoBroadcast::<u8, 2, 4>::new();
+        let first = ring.add_reader(LagPolicy::Skip).unwrap();
+        assert!(ring.push(1).is_ok());
+        assert!(ring.remove_reader(first));
+        assert!(!ring.remove_reader(first));
+
+        let second = ring.add_reader(LagPolicy::Skip).unwrap();
+        assert_ne!(first, second);
+        assert!(ring.push(2).is_ok());
+        assert_eq!(ring.read(first), Ok(None));
+        assert_eq!(ring.lag(first), None);
+        assert_eq!(ring.lag(second), Some(1));
+
+        let mut other = FrodoBroadcast::<u8, 2, 1>::new();
+        let own = other.add_reader(LagPolicy::Skip).unwrap();
+        assert!(other.push(3).is_ok());
+        for _ in 0..2 {
+            ring.add_reader(LagPolicy::Skip).unwrap();
+        }
+        let foreign = ring.add_reader(LagPolicy::Skip).unwrap();
+        assert!(ring.add_reader(LagPolicy::Skip).is_none());
+        assert_eq!(other.read(foreign), Ok(None));
+        assert_eq!(other.missed(foreign), None);
+        assert!(!other.remove_reader(foreign));
+        assert_eq!(ring.read(second), Ok(Some(2)));
+        assert_eq!(ring.read(own), Ok(None));
+        assert_eq!(other.read(own), Ok(Some(3)));
+    }
+}
@@ -0,0 +1,208 @@
+//! Пакетные операции над элементами очереди.
+
+use crate::FrodoRing;
+
+impl<T: Copy, const N: usize> FrodoRing<T, N> {
+    /// Добавляет элементы из среза в конец очереди, копируя их непрерывными участками.
+    ///
+    /// Возвращает число добавленных элементов: добавление останавливается, когда место заканчивается.
+    /// Во время транзакции и при заполненном буфере откатывается к поэлементному `push()`.
+    pub fn push_slice(&mut self, items: &[T]) -> usize {
+        let mut len = self.len() + self.reservations;
+        let mut pushed = 0usize;
+
+        while pushed < items.len() {
+            let count = (items.len() - pushed)
+                .min(N - self.cap)
+                .min(self.limit.saturating_sub(len));
+
+            if self.staging.is_some() || count == 0 {
+                match self.push(items[pushed]) {
+                    Ok(()) => {
+                        pushed += 1;
+                        len += 1;
+                        continue;
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            let tail = self.real_pos(self.cap);
+            let chunk = count.min(N - tail);
+            let buffer = self.buffer.as_mut_ptr() as *mut T;
+            unsafe {
+                core::ptr::copy_nonoverlapping(items[pushed..].as_ptr(), buffer.add(tail), chunk);
+                core::ptr::copy_nonoverlapping(
+                    items[pushed + chunk..].as_ptr(),
+                    buffer,
+                    count - chunk,
+                );
+            }
+
+            for naive_pos in self.cap..self.cap + count {
+                let real_pos = self.real_pos(naive_pos);
+                self.occupied[real_pos] = true;
+            }
+            self.cap += count;
+            #[cfg(feature = "journal")]
+            self.record(crate::journal::JournalOp::PushMany(count), self.cap - count);
+            self.check_watermarks();
+            self.note_depth();
+            #[cfg(feature = "stats")]
+            self.sample_depths(self.len() - count);
+            pushed += count;
+            len += count;
+        }
+
+        pushed
+    }
+
+    /// Изымает элементы из начала очереди в срез, копируя непрерывные серии занятых ячеек целиком.
+    ///
+    /// Возвращает число изъятых элементов.
+    pub fn pick_slice(&mut self, out: &mut [T]) -> usize {
+        let mut picked = 0usize;
+
+        while picked < out.len() && self.cap > 0 {
+            if !self.occupied[self.head] {
+                // Начало очереди зарезервировано
+                match self.pick() {
+                    Some(item) => {
+                        out[picked] = item;
+                        picked += 1;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            let limit = (out.len() - picked).min(self.cap).min(N - self.head);
+            let run = (0..limit)
+                .take_while(|i| self.occupied[self.head + i])
+                .count();
+
+            let buffer = self.buffer.as_ptr() as *const T;
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    buffer.add(self.head),
+                    out[picked..].as_mut_ptr(),
+                    run,
+                )
+            };
+
+            self.occupied[self.head..self.head + run].fill(false);
+            self.trim();
+            #[cfg(feature = "journal")]
+            self.record(crate::journal::JournalOp::TakeMany(run), 0);
+            #[cfg(feature = "stats")]
+            self.sample_depths(self.len() + run);
+            picked += run;
+        }
+
+        picked
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Показывает `f` до `max` первых элементов непрерывным срезом и изымает столько из них, сколько вернула `f`.
+    ///
+    /// Если первые элементы разорваны пропусками или концом буфера, очередь сперва ужимается и поворачивается
+    /// к началу буфера; при закреплённых элементах или открытой транзакции показывается лишь первая непрерывная
+    /// серия. Возвращает число изъятых элементов.
+    pub fn pick_batch<F: FnOnce(&[T]) -> usize>(&mut self, max: usize, f: F) -> usize {
+        let wanted = max.min(self.len());
+        if self.front_run(wanted) < wanted && !self.is_pinned() && self.staging.is_none() {
+            self.linearize();
+        }
+
+        let run = self.front_run(wanted);
+        let batch = unsafe {
+            core::slice::from_raw_parts(self.buffer.as_ptr().add(self.head).cast::<T>(), run)
+        };
+        let consumed = f(batch).min(run);
+
+        for real_pos in self.head..self.head + consumed {
+            self.occupied[real_pos] = false;
+            unsafe { self.buffer[real_pos].assume_init_drop() };
+        }
+        self.trim();
+        #[cfg(feature = "journal")]
+        if consumed > 0 {
+            self.record(crate::journal::JournalOp::TakeMany(consumed), 0);
+        }
+        #[cfg(feature = "stats")]
+        self.sample_depths(self.len() + consumed);
+        consumed
+    }
+
+    /// Длина непрерывной серии занятых ячеек от начала очереди до конца буфера, не больше `max`.
+    fn front_run(&self, max: usize) -> usize {
+        (self.head..N)
+            .take(max.min(self.cap))
+            .take_while(|real_pos| self.occupied[*real_pos])
+            .count()
+    }
+
+    /// Ужимает очередь и поворачивает буфер так, чтобы очередь начиналась с его первой ячейки.
+    fn linearize(&mut self) {
+        if self.len() < self.cap {
+            self.compact();
+        }
+
+        self.buffer.rotate_left(self.head);
+        self.occupied.rotate_left(self.head);
+        self.head = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FrodoRing;
+
+    #[test]
+    fn bulk_copy() {
+        let mut ring = FrodoRing::<u32, 8>::new();
+        assert_eq!(ring.push_slice(&[1, 2, 3, 4, 5, 6]), 6);
+        assert_eq!(ring.pick_slice(&mut [0; 4]), 4);
+
+        // Переход через конец буфера
+        assert_eq!(ring.push_slice(&[7, 8, 9, 10, 11, 12, 13]), 6);
+        assert_eq!(
+            ring.iter().copied().collect::<Vec<_>>(),
+            [5, 6, 7, 8, 9, 10, 11, 12]
+        );
+
+        // Пропуск внутри очереди: заполненный буфер ужимается
+        ring.remove_at(2);
+        assert_eq!(ring.push_slice(&[13, 14]), 1);
+        assert_eq!(
+            ring.iter().copied().collect::<Vec<_>>(),
+            [5, 6, 8, 9, 10, 11, 12, 13]
+        );
+
+        ring.remove_at(3);
+        let mut out = [0; 10];
+        assert_eq!(ring.pick_slice(&mut out), 7);
+        assert_eq!(out[..7], [5, 6, 8, 10, 11, 12, 13]);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn pick_batch() {
+        let mut ring = FrodoRing::<u32, 4>::new();
+        assert_eq!(ring.push_slice(&[1, 2, 3, 4]), 4);
+        assert_eq!(ring.pick_batch(2, |batch| batch.len()), 2);
+        assert_eq!(ring.push_slice(&[5, 6]), 2);
+        assert_eq!(ring.remove_at(1), Some(4));
+
+        // Очередь разорвана пропуском и концом буфера
+        let mut seen = Vec::new();
+        let consumed = ring.pick_batch(8, |batch| {
+            seen.extend_from_slice(batch);
+            2
+        });
+        assert_eq!(consumed, 2);
+        assert_eq!(seen, [3, 5, 6]);
+        assert!(ring.iter().eq([&6]));
+    }
+}
@@ -0,0 +1,172 @@
+//! Очередь команд с подтверждением и повторной отправкой.
+
+use crate::FrodoRing;
+
+/// Состояние команды в очереди.
+///
+/// Подтверждённые (`ack()`) и окончательно проваленные (`fail(.., false)`) команды покидают очередь.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandState {
+    /// Ожидает отправки.
+    Pending,
+    /// Отправлена и ожидает подтверждения.
+    InFlight,
+}
+
+/// Устойчивый к сжатию идентификатор команды в `FrodoCommandRing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommandHandle(u64);
+
+/// Команда с номером, состоянием и числом попыток отправки.
+struct Command<T> {
+    seq: u64,
+    state: CommandState,
+    attempts: u16,
+    item: T,
+}
+
+/// Очередь команд для надёжной отправки по ненадёжному каналу.
+///
+/// `take_next_pending()` выдаёт первую ожидающую команду и переводит её в `InFlight`; по ответу канала
+/// команда подтверждается (`ack()`) или проваливается (`fail()`) с возвратом в очередь на прежнее место.
+pub struct FrodoCommandRing<T, const N: usize> {
+    ring: FrodoRing<Command<T>, N>,
+    next_seq: u64,
+}
+
+impl<T, const N: usize> Default for FrodoCommandRing<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            next_seq: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoCommandRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает число команд в очереди, включая отправленные.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди команды.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Кладёт команду в очередь в состоянии `Pending`.
+    pub fn push(&mut self, item: T) -> Result<CommandHandle, T> {
+        let seq = self.next_seq;
+        let command = Command {
+            seq,
+            state: CommandState::Pending,
+            attempts: 0,
+            item,
+        };
+        self.ring.push(command).map_err(|command| command.item)?;
+        self.next_seq += 1;
+        Ok(CommandHandle(seq))
+    }
+
+    /// Находит реальную позицию команды по идентификатору.
+    fn real_pos(&self, handle: CommandHandle) -> Option<usize> {
+        let naive_pos = self.ring.position(|command| command.seq == handle.0)?;
+        self.ring.checked_real_pos(naive_pos)
+    }
+
+    fn command_mut(&mut self, handle: CommandHandle) -> Option<&mut Command<T>> {
+        let real_pos = self.real_pos(handle)?;
+        Some(unsafe { self.ring.buffer[real_pos].assume_init_mut() })
+    }
+
+    /// Переводит первую ожидающую команду в `InFlight` и выдаёт её для отправки.
+    pub fn take_next_pending(&mut self) -> Option<(CommandHandle, &T)> {
+        let naive_pos = self
+            .ring
+            .position(|command| command.state == CommandState::Pending)?;
+        let real_pos = self.ring.checked_real_pos(naive_pos)?;
+
+        let command = unsafe { self.ring.buffer[real_pos].assume_init_mut() };
+        command.state = CommandState::InFlight;
+        command.attempts = command.attempts.saturating_add(1);
+        Some((CommandHandle(command.seq), &command.item))
+    }
+
+    /// Возвращает состояние команды, если она ещё в очереди.
+    pub fn state(&self, handle: CommandHandle) -> Option<CommandState> {
+        let naive_pos = self.ring.position(|command| command.seq == handle.0)?;
+        self.ring.at(naive_pos).map(|command| command.state)
+    }
+
+    /// Возвращает число попыток отправки команды, если она ещё в очереди.
+    pub fn attempts(&self, handle: CommandHandle) -> Option<u16> {
+        let naive_pos = self.ring.position(|command| command.seq == handle.0)?;
+        self.ring.at(naive_pos).map(|command| command.attempts)
+    }
+
+    /// Подтверждает отправленную команду, изымая её из очереди.
+    ///
+    /// Возвращает `None`, если команды нет или она не в состоянии `InFlight`.
+    pub fn ack(&mut self, handle: CommandHandle) -> Option<T> {
+        self.finish(handle)
+    }
+
+    /// Отмечает провал отправленной команды.
+    ///
+    /// При `requeue` команда возвращается в `Pending` на прежнее место и будет выдана снова;
+    /// иначе она изымается и возвращается. Возвращает `None`, если команды нет, она не в состоянии `InFlight`
+    /// или была возвращена в очередь.
+    pub fn fail(&mut self, handle: CommandHandle, requeue: bool) -> Option<T> {
+        if !requeue {
+            return self.finish(handle);
+        }
+
+        let command = self.command_mut(handle)?;
+        if command.state == CommandState::InFlight {
+            command.state = CommandState::Pending;
+        }
+        None
+    }
+
+    /// Изымает отправленную команду.
+    fn finish(&mut self, handle: CommandHandle) -> Option<T> {
+        let naive_pos = self.ring.position(|command| command.seq == handle.0)?;
+        if self.ring.at(naive_pos)?.state != CommandState::InFlight {
+            return None;
+        }
+        self.ring.remove_at(naive_pos).map(|command| command.item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_ring() {
+        let mut ring = FrodoCommandRing::<&str, 4>::new();
+        let reset = ring.push("reset").unwrap();
+        let read = ring.push("read").unwrap();
+
+        assert_eq!(ring.take_next_pending(), Some((reset, &"reset")));
+        assert_eq!(ring.take_next_pending(), Some((read, &"read")));
+        assert_eq!(ring.take_next_pending(), None);
+        assert_eq!(ring.ack(read), Some("read"));
+        assert_eq!(ring.ack(read), None);
+
+        // Таймаут: повторяем
+        assert_eq!(ring.fail(reset, true), None);
+        assert_eq!(ring.state(reset), Some(CommandState::Pending));
+        assert_eq!(ring.ack(reset), None);
+        assert_eq!(ring.take_next_pending(), Some((reset, &"reset")));
+        assert_eq!(ring.attempts(reset), Some(2));
+
+        assert_eq!(ring.fail(reset, false), Some("reset"));
+        assert!(ring.is_empty());
+    }
+}
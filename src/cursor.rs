@@ -0,0 +1,74 @@
+//! Курсор чтения с явной фиксацией прочитанного.
+
+use crate::FrodoRing;
+
+/// Курсор, читающий очередь без изъятия; изъятие происходит только при `commit()`.
+///
+/// Если курсор удалён без `commit()` (например, обработка прервалась сбоем), прочитанные после последней
+/// фиксации элементы остаются в очереди и будут выданы снова: доставка "хотя бы один раз".
+pub struct FrodoCursor<'ring, T, const N: usize> {
+    ring: &'ring mut FrodoRing<T, N>,
+    /// Наивная позиция, с которой продолжится чтение.
+    offset: usize,
+}
+
+impl<T, const N: usize> FrodoCursor<'_, T, N> {
+    /// Выдаёт следующий элемент, сдвигая предварительное смещение.
+    pub fn consume(&mut self) -> Option<&T> {
+        while self.offset < self.ring.cap {
+            let real_pos = self.ring.real_pos(self.offset);
+            self.offset += 1;
+            if self.ring.occupied[real_pos] {
+                return Some(unsafe { self.ring.buffer[real_pos].assume_init_ref() });
+            }
+        }
+        None
+    }
+
+    /// Изымает из очереди все выданные элементы; возвращает их число.
+    pub fn commit(&mut self) -> usize {
+        let removed = self.ring.remove_range(..self.offset);
+        self.offset = 0;
+        removed
+    }
+
+    /// Возвращает курсор к последней фиксации, чтобы выдать незафиксированные элементы снова.
+    pub fn rewind(&mut self) {
+        self.offset = 0;
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает курсор чтения с начала очереди.
+    pub fn cursor(&mut self) -> FrodoCursor<'_, T, N> {
+        FrodoCursor {
+            ring: self,
+            offset: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        for i in 1..=4 {
+            assert!(ring.push(i).is_ok());
+        }
+        assert_eq!(ring.remove_at(1), Some(2));
+
+        let mut cursor = ring.cursor();
+        assert_eq!(cursor.consume(), Some(&1));
+        assert_eq!(cursor.consume(), Some(&3));
+        assert_eq!(cursor.commit(), 2);
+        assert_eq!(cursor.consume(), Some(&4));
+        assert_eq!(cursor.consume(), None);
+
+        // Сбой до фиксации: элемент будет выдан снова
+        assert_eq!(ring.len(), 1);
+        assert_eq!(ring.cursor().consume(), Some(&4));
+    }
+}
@@ -0,0 +1,58 @@
+//! Прореживающие итераторы для понижения частоты отсчётов.
+
+use crate::FrodoRing;
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Создаёт итератор по каждому `step`-му элементу очереди, начиная с первого.
+    ///
+    /// # Panics
+    ///
+    /// Если `step == 0`.
+    pub fn iter_step_by(&self, step: usize) -> impl Iterator<Item = &T> {
+        self.iter().step_by(step)
+    }
+
+    /// Создаёт итератор, сворачивающий каждые `step` подряд идущих элементов в одно значение.
+    ///
+    /// Каждая группа сворачивается `fold` от значения `init()`; последняя группа может быть короче.
+    /// Так можно выдавать минимум, максимум или среднее по группам, не создавая промежуточного буфера.
+    ///
+    /// # Panics
+    ///
+    /// Если `step == 0`.
+    pub fn iter_grouped<A, I, F>(
+        &self,
+        step: usize,
+        init: I,
+        mut fold: F,
+    ) -> impl Iterator<Item = A>
+    where
+        I: Fn() -> A,
+        F: FnMut(A, &T) -> A,
+    {
+        assert!(step > 0, "step must be greater than zero");
+
+        let mut iter = self.iter().peekable();
+        core::iter::from_fn(move || {
+            iter.peek()?;
+            Some(iter.by_ref().take(step).fold(init(), &mut fold))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimation() {
+        let ring = FrodoRing::<u32, 7>::from_fn(|i| i as u32 * 10);
+        assert!(ring.iter_step_by(3).eq([&0, &30, &60]));
+
+        let max = ring.iter_grouped(3, || 0, |max, x| max.max(*x));
+        assert!(max.eq([20, 50, 60]));
+
+        let mean = ring.iter_grouped(2, || (0, 0), |(sum, n), x| (sum + x, n + 1));
+        assert!(mean.map(|(sum, n)| sum / n).eq([5, 25, 45, 60]));
+    }
+}
@@ -0,0 +1,143 @@
+//! Очередь числовых отсчётов, хранящая разности соседних значений.
+
+use crate::FrodoRing;
+
+/// Метка ячейки, за которой следует полная разность в двух ячейках.
+const ESCAPE: i16 = i16::MIN;
+
+/// Очередь отсчётов `i32`, хранящая вместо значений их разности в ячейках `i16`.
+///
+/// Для монотонных и медленно меняющихся рядов (счётчики, температура, отметки времени) это вдвое экономит
+/// память. Разность, не помещающаяся в `i16`, хранится в трёх ячейках, поэтому `N` - число ячеек,
+/// а не отсчётов. Значения восстанавливаются при чтении; разности считаются с переносом, так что ряды `u32`
+/// можно хранить через `as i32`.
+pub struct FrodoDeltaRing<const N: usize> {
+    /// Разности отсчётов, следующих за первым.
+    deltas: FrodoRing<i16, N>,
+    /// Первый (старейший) отсчёт.
+    first: i32,
+    /// Последний отсчёт.
+    last: i32,
+    len: usize,
+}
+
+impl<const N: usize> Default for FrodoDeltaRing<N> {
+    fn default() -> Self {
+        Self {
+            deltas: FrodoRing::new(),
+            first: 0,
+            last: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> FrodoDeltaRing<N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает число отсчётов в очереди.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Сообщает, есть ли в очереди отсчёты.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Кладёт отсчёт в очередь; возвращает его обратно, если для его разности нет места.
+    pub fn push(&mut self, value: i32) -> Result<(), i32> {
+        if self.len == 0 {
+            self.first = value;
+        } else {
+            let delta = value.wrapping_sub(self.last);
+            let free = N - self.deltas.len();
+            match i16::try_from(delta) {
+                Ok(delta) if delta != ESCAPE && free >= 1 => {
+                    let _ = self.deltas.push(delta);
+                }
+                Err(_) | Ok(ESCAPE) if free >= 3 => {
+                    let _ = self.deltas.push(ESCAPE);
+                    let _ = self.deltas.push((delta >> 16) as i16);
+                    let _ = self.deltas.push(delta as i16);
+                }
+                _ => return Err(value),
+            }
+        }
+
+        self.last = value;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Возвращает первый отсчёт, не изымая его.
+    pub fn peek(&self) -> Option<i32> {
+        (self.len > 0).then_some(self.first)
+    }
+
+    /// Отдаёт первый отсчёт, изымая его из очереди.
+    pub fn pick(&mut self) -> Option<i32> {
+        let value = self.peek()?;
+        self.len -= 1;
+        if self.len > 0 {
+            let mut cells = core::iter::from_fn(|| self.deltas.pick());
+            self.first = value.wrapping_add(decode(&mut cells));
+        }
+        Some(value)
+    }
+
+    /// Создаёт итератор по восстановленным отсчётам от старого к новому.
+    pub fn iter(&self) -> impl Iterator<Item = i32> + '_ {
+        let mut cells = self.deltas.iter().copied();
+        let mut value = self.first;
+        (0..self.len).map(move |i| {
+            if i > 0 {
+                value = value.wrapping_add(decode(&mut cells));
+            }
+            value
+        })
+    }
+}
+
+/// Читает одну разность из ячеек.
+fn decode<I: Iterator<Item = i16>>(cells: &mut I) -> i32 {
+    match cells.next() {
+        Some(ESCAPE) => {
+            let high = cells.next().unwrap_or_default() as u16 as u32;
+            let low = cells.next().unwrap_or_default() as u16 as u32;
+            ((high << 16) | low) as i32
+        }
+        delta => delta.unwrap_or_default() as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_ring() {
+        let mut ring = FrodoDeltaRing::<6>::new();
+        assert!(ring.push(1000).is_ok());
+        assert!(ring.push(1010).is_ok());
+        assert!(ring.push(990).is_ok());
+        assert!(ring.push(i32::MIN).is_ok());
+        assert!(ring.push(i32::MIN + 5).is_ok());
+        assert_eq!(ring.push(5), Err(5));
+        assert_eq!(ring.len(), 5);
+        assert!(ring.iter().eq([1000, 1010, 990, i32::MIN, i32::MIN + 5]));
+
+        assert_eq!(ring.pick(), Some(1000));
+        assert_eq!(ring.pick(), Some(1010));
+
+        // Разность ровно i16::MIN тоже требует трёх ячеек
+        let wrapped = (i32::MIN + 5).wrapping_sub(32768);
+        assert_eq!(ring.push(wrapped), Err(wrapped));
+        assert_eq!(ring.pick(), Some(990));
+        assert!(ring.push(wrapped).is_ok());
+        assert!(ring.iter().eq([i32::MIN, i32::MIN + 5, wrapped]));
+    }
+}
@@ -0,0 +1,111 @@
+//! Очередь с выдачей элементов по принципу "ближайший дедлайн - первым" (EDF).
+
+use crate::FrodoRing;
+
+/// Очередь, каждый элемент которой снабжён дедлайном в тиках монотонных часов.
+///
+/// `pick()` изымает элемент с ближайшим дедлайном прямо из середины очереди, не перемещая остальные элементы;
+/// элементы с одинаковыми дедлайнами выдаются в порядке FIFO.
+pub struct FrodoEdf<T, const N: usize> {
+    ring: FrodoRing<(u64, T), N>,
+}
+
+impl<T, const N: usize> Default for FrodoEdf<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoEdf<T, N> {
+    /// Находит наивную позицию элемента с ближайшим дедлайном.
+    fn earliest(&self) -> Option<isize> {
+        let mut earliest: Option<(isize, u64)> = None;
+
+        for naive_pos in 0..self.ring.used() as isize {
+            if let Some((deadline, _)) = self.ring.at(naive_pos) {
+                if earliest.is_none_or(|(_, d)| *deadline < d) {
+                    earliest = Some((naive_pos, *deadline));
+                }
+            }
+        }
+
+        earliest.map(|(naive_pos, _)| naive_pos)
+    }
+
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Кладёт элемент с дедлайном `deadline` в очередь.
+    pub fn push(&mut self, deadline: u64, item: T) -> Result<(), T> {
+        self.ring.push((deadline, item)).map_err(|(_, item)| item)
+    }
+
+    /// Возвращает элемент с ближайшим дедлайном вместе с самим дедлайном, не изымая его.
+    pub fn peek(&self) -> Option<(u64, &T)> {
+        let (deadline, item) = self.ring.at(self.earliest()?)?;
+        Some((*deadline, item))
+    }
+
+    /// Отдаёт элемент с ближайшим дедлайном, изымая его из очереди.
+    pub fn pick(&mut self) -> Option<T> {
+        let naive_pos = self.earliest()?;
+        self.ring.remove_at(naive_pos).map(|(_, item)| item)
+    }
+
+    /// Возвращает ближайший дедлайн среди элементов очереди.
+    pub fn next_deadline(&self) -> Option<u64> {
+        self.peek().map(|(deadline, _)| deadline)
+    }
+
+    /// Сообщает, сколько тиков можно спать, начиная с момента `now`, до ближайшего дедлайна.
+    ///
+    /// Возвращает `Some(0)`, если ближайший дедлайн уже наступил, и `None`, если очередь пуста.
+    pub fn sleep_for(&self, now: u64) -> Option<u64> {
+        self.next_deadline()
+            .map(|deadline| deadline.saturating_sub(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edf() {
+        let mut edf = FrodoEdf::<u8, 4>::new();
+
+        assert_eq!(edf.next_deadline(), None);
+        assert!(edf.push(30, 0x1).is_ok());
+        assert!(edf.push(10, 0x2).is_ok());
+        assert!(edf.push(20, 0x3).is_ok());
+        assert!(edf.push(10, 0x4).is_ok());
+        assert_eq!(edf.push(5, 0x5), Err(0x5));
+
+        assert_eq!(edf.next_deadline(), Some(10));
+        assert_eq!(edf.sleep_for(4), Some(6));
+        assert_eq!(edf.sleep_for(15), Some(0));
+
+        assert_eq!(edf.pick(), Some(0x2));
+        assert_eq!(edf.pick(), Some(0x4));
+        assert!(edf.push(25, 0x6).is_ok());
+        assert_eq!(edf.pick(), Some(0x3));
+        assert_eq!(edf.pick(), Some(0x6));
+        assert_eq!(edf.pick(), Some(0x1));
+        assert_eq!(edf.pick(), None);
+        assert!(edf.is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+//! Вытеснение по приоритету при заполненной очереди.
+
+use crate::FrodoRing;
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Задаёт извлекатель приоритета: после этого `push()` в заполненную очередь вытесняет самый старый
+    /// элемент с наименьшим приоритетом, если приоритет нового элемента выше, вместо отказа.
+    ///
+    /// Так критичные сообщения (например, аварии) не теряются в пользу рядовой телеметрии.
+    /// Вытесненный элемент уничтожается; чтобы получить его обратно, используйте `push_weighted()`.
+    pub fn set_eviction_priority(&mut self, priority: fn(&T) -> u8) {
+        self.eviction = Some(priority);
+    }
+
+    /// Отключает вытеснение по приоритету: `push()` в заполненную очередь снова возвращает элемент.
+    pub fn clear_eviction_priority(&mut self) {
+        self.eviction = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        Telemetry(u8),
+        Alarm(u8),
+    }
+
+    fn priority(message: &Message) -> u8 {
+        match message {
+            Message::Telemetry(_) => 0,
+            Message::Alarm(_) => 1,
+        }
+    }
+
+    #[test]
+    fn eviction_priority() {
+        let mut ring = FrodoRing::<Message, 3>::new();
+        ring.set_eviction_priority(priority);
+
+        assert!(ring.push(Message::Telemetry(1)).is_ok());
+        assert!(ring.push(Message::Alarm(1)).is_ok());
+        assert!(ring.push(Message::Telemetry(2)).is_ok());
+        assert_eq!(ring.push(Message::Telemetry(3)), Err(Message::Telemetry(3)));
+
+        assert!(ring.push(Message::Alarm(2)).is_ok());
+        assert!(ring.push(Message::Alarm(3)).is_ok());
+        assert_eq!(ring.push(Message::Alarm(4)), Err(Message::Alarm(4)));
+        assert!(
+            ring.iter()
+                .eq([&Message::Alarm(1), &Message::Alarm(2), &Message::Alarm(3)])
+        );
+
+        ring.clear_eviction_priority();
+        assert!(ring.pick().is_some());
+        assert!(ring.push(Message::Telemetry(4)).is_ok());
+        assert_eq!(ring.push(Message::Alarm(5)), Err(Message::Alarm(5)));
+    }
+}
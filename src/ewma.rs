@@ -0,0 +1,88 @@
+//! Экспоненциальное сглаживание поверх истории отсчётов.
+
+use crate::FrodoRing;
+
+/// История последних `N` отсчётов вместе с `K` экспоненциально взвешенными средними.
+///
+/// Каждое среднее обновляется при добавлении отсчёта как `avg += alpha * (x - avg)` со своим `alpha`
+/// (например, быстрый и медленный фильтры). Первый отсчёт задаёт начальное значение всех средних.
+pub struct FrodoEwma<T, const N: usize, const K: usize> {
+    history: FrodoRing<T, N>,
+    alphas: [f32; K],
+    averages: [f32; K],
+    primed: bool,
+}
+
+impl<T: Copy + Into<f32>, const N: usize, const K: usize> FrodoEwma<T, N, K> {
+    /// Создаёт пустую историю со средними для коэффициентов `alphas` (`0.0..=1.0`).
+    pub fn new(alphas: [f32; K]) -> Self {
+        Self {
+            history: FrodoRing::new(),
+            alphas,
+            averages: [0.0; K],
+            primed: false,
+        }
+    }
+
+    /// Возвращает историю отсчётов от старого к новому.
+    pub fn history(&self) -> &FrodoRing<T, N> {
+        &self.history
+    }
+
+    /// Добавляет отсчёт и обновляет средние; если история заполнена, старейший отсчёт вытесняется и возвращается.
+    pub fn push(&mut self, sample: T) -> Option<T> {
+        let x = sample.into();
+        for (average, alpha) in self.averages.iter_mut().zip(self.alphas) {
+            *average = if self.primed {
+                *average + alpha * (x - *average)
+            } else {
+                x
+            };
+        }
+        self.primed = true;
+
+        let evicted = if self.history.len() == N {
+            self.history.pick()
+        } else {
+            None
+        };
+        let _ = self.history.push(sample);
+        evicted
+    }
+
+    /// Возвращает среднее с коэффициентом `alphas[i]`, если отсчёты уже были.
+    pub fn average(&self, i: usize) -> Option<f32> {
+        self.primed.then(|| self.averages.get(i).copied()).flatten()
+    }
+
+    /// Возвращает все средние, если отсчёты уже были.
+    pub fn averages(&self) -> Option<[f32; K]> {
+        self.primed.then_some(self.averages)
+    }
+
+    /// Сбрасывает средние, сохраняя историю; следующий отсчёт задаст их заново.
+    pub fn reset(&mut self) {
+        self.primed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ewma() {
+        let mut filter = FrodoEwma::<u8, 3, 2>::new([0.5, 0.25]);
+        assert_eq!(filter.averages(), None);
+
+        assert_eq!(filter.push(8), None);
+        assert_eq!(filter.averages(), Some([8.0, 8.0]));
+        filter.push(16);
+        assert_eq!(filter.averages(), Some([12.0, 10.0]));
+        filter.push(0);
+        assert_eq!(filter.push(4), Some(8));
+        assert_eq!(filter.average(0), Some(5.0));
+        assert_eq!(filter.average(2), None);
+        assert!(filter.history().iter().eq([&16, &0, &4]));
+    }
+}
@@ -0,0 +1,92 @@
+//! Вытеснение просроченных элементов.
+
+use crate::FrodoRing;
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Удаляет из очереди все элементы, дедлайн которых (`deadline`) уже наступил к моменту `now`.
+    ///
+    /// Возвращает число удалённых элементов.
+    pub fn remove_expired<F: Fn(&T) -> u64>(&mut self, now: u64, deadline: F) -> usize {
+        let mut removed = 0usize;
+        for naive_pos in 0..self.cap {
+            let real_pos = self.real_pos(naive_pos);
+            if self.occupied[real_pos]
+                && deadline(unsafe { self.buffer[real_pos].assume_init_ref() }) <= now
+            {
+                self.occupied[real_pos] = false;
+                unsafe { self.buffer[real_pos].assume_init_drop() };
+                removed += 1;
+            }
+        }
+
+        self.trim();
+        #[cfg(feature = "journal")]
+        if removed > 0 {
+            self.record(crate::journal::JournalOp::TakeMany(removed), 0);
+        }
+        removed
+    }
+
+    /// Кладёт элемент в очередь, а при её заполненности сперва удаляет просроченные к моменту `now` элементы.
+    ///
+    /// Возвращает число удалённых просроченных элементов; если места не нашлось и после этого,
+    /// элемент возвращается обратно. Вытеснение по приоритету (`set_eviction_priority()`) применяется
+    /// лишь тогда, когда просроченных элементов не осталось.
+    pub fn push_expiring<F: Fn(&T) -> u64>(
+        &mut self,
+        item: T,
+        now: u64,
+        deadline: F,
+    ) -> Result<usize, T> {
+        let item = match self.push_free(item) {
+            Ok(()) => return Ok(0),
+            Err(item) => item,
+        };
+
+        let removed = self.remove_expired(now, deadline);
+        self.push(item).map(|()| removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FrodoRing;
+
+    #[test]
+    fn push_expiring() {
+        let mut ring = FrodoRing::<(u64, char), 3>::new();
+        let deadline = |(deadline, _): &(u64, char)| *deadline;
+
+        assert_eq!(ring.push_expiring((30, 'a'), 0, deadline), Ok(0));
+        assert_eq!(ring.push_expiring((10, 'b'), 0, deadline), Ok(0));
+        assert_eq!(ring.push_expiring((15, 'c'), 0, deadline), Ok(0));
+
+        assert_eq!(ring.push_expiring((40, 'd'), 5, deadline), Err((40, 'd')));
+        assert_eq!(ring.push_expiring((40, 'd'), 20, deadline), Ok(2));
+        assert_eq!(ring.iter().map(|(_, c)| *c).collect::<String>(), "ad");
+    }
+
+    #[test]
+    fn push_expiring_before_eviction() {
+        let mut ring = FrodoRing::<(u64, u8), 2>::new();
+        ring.set_eviction_priority(|(_, priority)| *priority);
+        let deadline = |(deadline, _): &(u64, u8)| *deadline;
+
+        assert!(ring.push((5, 9)).is_ok());
+        assert!(ring.push((200, 1)).is_ok());
+
+        // Просроченный элемент удаляется раньше, чем вытесняется живой элемент с низким приоритетом
+        assert_eq!(ring.push_expiring((300, 3), 10, deadline), Ok(1));
+        assert_eq!(
+            ring.iter().copied().collect::<Vec<_>>(),
+            [(200, 1), (300, 3)]
+        );
+
+        assert_eq!(ring.push_expiring((400, 4), 10, deadline), Ok(0));
+        assert_eq!(
+            ring.iter().copied().collect::<Vec<_>>(),
+            [(300, 3), (400, 4)]
+        );
+        assert_eq!(ring.rejections(), 0);
+    }
+}
@@ -0,0 +1,150 @@
+//! Интерфейс для кода на C (при включённой функции `ffi`).
+//!
+//! Очередь хранит значения размером с указатель (`uintptr_t`) и размещается в памяти, выделенной вызывающей стороной:
+//!
+//! ```c
+//! static _Alignas(16) uint8_t mem[1024];
+//! FrodoFfiRing *ring = frodo_ring_init(mem, sizeof(mem)); // sizeof(mem) >= frodo_ring_size()
+//! frodo_ring_push(ring, 42);
+//! uintptr_t value;
+//! while (frodo_ring_pick(ring, &value)) { ... }
+//! ```
+
+use core::ffi::c_void;
+
+use crate::FrodoRing;
+
+/// Ёмкость очереди, доступной через C.
+pub const FRODO_FFI_CAPACITY: usize = 64;
+
+/// Непрозрачный дескриптор очереди для кода на C.
+#[repr(C)]
+pub struct FrodoFfiRing {
+    ring: FrodoRing<usize, FRODO_FFI_CAPACITY>,
+}
+
+/// Возвращает размер памяти, необходимой для `frodo_ring_init()`.
+#[unsafe(no_mangle)]
+pub extern "C" fn frodo_ring_size() -> usize {
+    core::mem::size_of::<FrodoFfiRing>()
+}
+
+/// Возвращает необходимое выравнивание памяти для `frodo_ring_init()`.
+#[unsafe(no_mangle)]
+pub extern "C" fn frodo_ring_align() -> usize {
+    core::mem::align_of::<FrodoFfiRing>()
+}
+
+/// Создаёт пустую очередь в памяти `mem` размером `size` байт.
+///
+/// Возвращает null, если память не задана, мала или неверно выровнена.
+///
+/// # Safety
+///
+/// `mem` должна быть доступна для записи на `size` байт и не использоваться ничем другим, пока жива очередь.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn frodo_ring_init(mem: *mut c_void, size: usize) -> *mut FrodoFfiRing {
+    let ring = mem as *mut FrodoFfiRing;
+    if ring.is_null() || size < frodo_ring_size() || !ring.is_aligned() {
+        return core::ptr::null_mut();
+    }
+
+    unsafe {
+        ring.write(FrodoFfiRing {
+            ring: FrodoRing::new(),
+        })
+    };
+    ring
+}
+
+/// Кладёт значение в очередь. Возвращает `false`, если места нет.
+///
+/// # Safety
+///
+/// `ring` должен быть получен из `frodo_ring_init()` и не использоваться одновременно из другого контекста.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn frodo_ring_push(ring: *mut FrodoFfiRing, value: usize) -> bool {
+    unsafe { &mut *ring }.ring.push(value).is_ok()
+}
+
+/// Изымает первое значение очереди в `out`. Возвращает `false`, если очередь пуста.
+///
+/// # Safety
+///
+/// Как у `frodo_ring_push()`; кроме того, `out` должен быть доступен для записи.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn frodo_ring_pick(ring: *mut FrodoFfiRing, out: *mut usize) -> bool {
+    match unsafe { &mut *ring }.ring.pick() {
+        Some(value) => {
+            unsafe { out.write(value) };
+            true
+        }
+        None => false,
+    }
+}
+
+/// Изымает значение из ячейки по наивной позиции в `out`. Возвращает `false`, если ячейка пуста.
+///
+/// # Safety
+///
+/// Как у `frodo_ring_pick()`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn frodo_ring_remove_at(
+    ring: *mut FrodoFfiRing,
+    naive_pos: isize,
+    out: *mut usize,
+) -> bool {
+    match unsafe { &mut *ring }.ring.remove_at(naive_pos) {
+        Some(value) => {
+            unsafe { out.write(value) };
+            true
+        }
+        None => false,
+    }
+}
+
+/// Возвращает число значений в очереди.
+///
+/// # Safety
+///
+/// `ring` должен быть получен из `frodo_ring_init()`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn frodo_ring_len(ring: *const FrodoFfiRing) -> usize {
+    unsafe { &*ring }.ring.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::MaybeUninit;
+
+    use super::*;
+
+    #[test]
+    fn ffi_in_place() {
+        let mut mem = MaybeUninit::<FrodoFfiRing>::uninit();
+        let mem = mem.as_mut_ptr() as *mut c_void;
+
+        unsafe {
+            assert!(frodo_ring_init(mem, frodo_ring_size() - 1).is_null());
+            assert!(
+                frodo_ring_init((mem as *mut u8).add(1) as *mut c_void, frodo_ring_size())
+                    .is_null()
+            );
+
+            let ring = frodo_ring_init(mem, frodo_ring_size());
+            assert!(frodo_ring_push(ring, 1));
+            assert!(frodo_ring_push(ring, 2));
+            assert!(frodo_ring_push(ring, 3));
+            assert_eq!(frodo_ring_len(ring), 3);
+
+            let mut value = 0usize;
+            assert!(frodo_ring_remove_at(ring, 1, &mut value));
+            assert_eq!(value, 2);
+            assert!(frodo_ring_pick(ring, &mut value));
+            assert_eq!(value, 1);
+            assert!(frodo_ring_pick(ring, &mut value));
+            assert!(!frodo_ring_pick(ring, &mut value));
+            assert_eq!(value, 3);
+        }
+    }
+}
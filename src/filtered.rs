@@ -0,0 +1,64 @@
+//! Ленивое представление подмножества элементов очереди.
+
+use crate::FrodoRing;
+
+/// Представление очереди, видящее только элементы, отвечающие условию.
+///
+/// Ничего не копирует и не изымает: условие проверяется при каждом обращении.
+pub struct FrodoRingFiltered<'ring, T, const N: usize, F> {
+    ring: &'ring FrodoRing<T, N>,
+    predicate: F,
+}
+
+impl<'ring, T, const N: usize, F: Fn(&T) -> bool> FrodoRingFiltered<'ring, T, N, F> {
+    /// Возвращает число элементов, отвечающих условию.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Сообщает, есть ли в очереди элементы, отвечающие условию.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Получает `pos`-й по очереди элемент среди отвечающих условию.
+    pub fn get(&self, pos: usize) -> Option<&'ring T> {
+        self.iter().nth(pos)
+    }
+
+    /// Создаёт итератор по элементам, отвечающим условию.
+    pub fn iter(&self) -> impl Iterator<Item = &'ring T> + '_ {
+        self.ring.iter().filter(|el| (self.predicate)(el))
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает представление очереди, содержащее только элементы, отвечающие условию.
+    pub fn filtered<F: Fn(&T) -> bool>(&self, predicate: F) -> FrodoRingFiltered<'_, T, N, F> {
+        FrodoRingFiltered {
+            ring: self,
+            predicate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FrodoRing;
+
+    #[test]
+    fn filtered() {
+        let mut ring = FrodoRing::<u8, 6>::new();
+        for i in 1..=6 {
+            ring.push(i).unwrap();
+        }
+        ring.remove_at(3);
+
+        let urgent = ring.filtered(|el| el % 2 == 0);
+        assert_eq!(urgent.len(), 2);
+        assert_eq!(urgent.get(1), Some(&6));
+        assert_eq!(urgent.get(2), None);
+        assert_eq!(urgent.iter().copied().collect::<Vec<_>>(), [2, 6]);
+        assert!(ring.filtered(|el| *el > 6).is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+//! Байтовая очередь как приёмник форматированного вывода.
+
+use core::fmt;
+
+use crate::FrodoRing;
+
+impl<const N: usize> fmt::Write for FrodoRing<u8, N> {
+    /// Кладёт байты строки в очередь.
+    ///
+    /// Если места на всю строку нет, ничего из неё не кладёт и возвращает ошибку. Учтите, что `write!`
+    /// вызывает этот метод для каждого фрагмента, так что от сообщения может остаться начало. Закреплённая
+    /// очередь с пропусками, которую нельзя сжать, может принять и строку частично.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.limit.saturating_sub(self.limited_len()) < s.len() {
+            return Err(fmt::Error);
+        }
+
+        if self.push_slice(s.as_bytes()) == s.len() {
+            Ok(())
+        } else {
+            Err(fmt::Error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use crate::FrodoRing;
+
+    #[test]
+    fn fmt_write() {
+        let mut log = FrodoRing::<u8, 16>::new();
+        write!(log, "irq {}: {:#x}", 7, 255).unwrap();
+        assert_eq!(log.iter().copied().collect::<Vec<_>>(), b"irq 7: 0xff");
+
+        assert!(write!(log, " overflow").is_err());
+        assert_eq!(log.len(), 11);
+
+        let mut drained = [0u8; 16];
+        let n = log.pick_slice(&mut drained);
+        assert_eq!(&drained[..n], b"irq 7: 0xff");
+    }
+}
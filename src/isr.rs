@@ -0,0 +1,210 @@
+//! Кольцевая очередь для схемы "прерывание кладёт, основной цикл забирает".
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Очередь с одним производителем и одним потребителем без критических секций.
+///
+/// В отличие от `FrodoRing`, не поддерживает изъятие из середины и сжатие: `push()` всегда занимает `O(1)`
+/// без циклов, а индексы начала и конца - атомарные, так что безопасно использовать очередь из прерывания
+/// (производитель) и основного цикла (потребитель) без отключения прерываний. Требуются лишь атомарные
+/// загрузка и сохранение `usize`, без сравнения с обменом.
+///
+/// Роли разделяются через `split()`: `IsrProducer` может только класть элементы, `IsrConsumer` - только забирать.
+///
+/// Счётчики начала и конца свободно переполняются, поэтому ёмкость `N` должна быть степенью двойки:
+/// иначе `% N` после переполнения указывает не на ту ячейку. Другие значения отвергаются при компиляции.
+pub struct IsrRing<T, const N: usize> {
+    buffer: UnsafeCell<[MaybeUninit<T>; N]>,
+    /// Счётчик забранных элементов; изменяется только потребителем.
+    head: AtomicUsize,
+    /// Счётчик положенных элементов; изменяется только производителем.
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for IsrRing<T, N> {}
+
+impl<T, const N: usize> Default for IsrRing<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for IsrRing<T, N> {
+    fn drop(&mut self) {
+        while self.consumer_poll().is_some() {}
+    }
+}
+
+impl<T, const N: usize> IsrRing<T, N> {
+    /// Ёмкость, не являющаяся степенью двойки (в том числе нулевая), отвергается при компиляции.
+    const POWER_OF_TWO: () = assert!(
+        N.is_power_of_two(),
+        "IsrRing capacity N must be a power of two"
+    );
+
+    /// Создаёт новую очередь; может использоваться для инициализации `static`.
+    pub const fn new() -> Self {
+        let () = Self::POWER_OF_TWO;
+
+        Self {
+            buffer: UnsafeCell::new([const { MaybeUninit::uninit() }; N]),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Возвращает число элементов в очереди на момент вызова.
+    pub fn len(&self) -> usize {
+        self.tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    /// Сообщает, есть ли в очереди элементы на момент вызова.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Разделяет очередь на производителя и потребителя.
+    pub fn split(&mut self) -> (IsrProducer<'_, T, N>, IsrConsumer<'_, T, N>) {
+        (IsrProducer { ring: self }, IsrConsumer { ring: self })
+    }
+
+    fn producer_push(&self, item: T) -> Result<(), T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == N {
+            return Err(item);
+        }
+
+        unsafe { (*self.buffer.get())[tail % N].write(item) };
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    fn consumer_poll(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+
+        let item = unsafe { (*self.buffer.get())[head % N].assume_init_read() };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(item)
+    }
+}
+
+/// Сторона производителя `IsrRing` (например, обработчик прерывания).
+pub struct IsrProducer<'ring, T, const N: usize> {
+    ring: &'ring IsrRing<T, N>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for IsrProducer<'_, T, N> {}
+
+impl<T, const N: usize> IsrProducer<'_, T, N> {
+    /// Кладёт элемент в очередь за `O(1)`; возвращает его обратно, если очередь заполнена.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        self.ring.producer_push(item)
+    }
+
+    /// Сообщает, заполнена ли очередь на момент вызова.
+    pub fn is_full(&self) -> bool {
+        self.ring.len() == N
+    }
+}
+
+/// Сторона потребителя `IsrRing` (например, основной цикл).
+pub struct IsrConsumer<'ring, T, const N: usize> {
+    ring: &'ring IsrRing<T, N>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for IsrConsumer<'_, T, N> {}
+
+impl<T, const N: usize> IsrConsumer<'_, T, N> {
+    /// Забирает первый элемент очереди, если он есть.
+    pub fn poll(&mut self) -> Option<T> {
+        self.ring.consumer_poll()
+    }
+
+    /// Возвращает число элементов в очереди на момент вызова.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы на момент вызова.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isr_ring() {
+        let mut ring = IsrRing::<u8, 2>::new();
+        let (mut producer, mut consumer) = ring.split();
+
+        assert_eq!(consumer.poll(), None);
+        assert!(producer.push(0x1).is_ok());
+        assert!(producer.push(0x2).is_ok());
+        assert!(producer.is_full());
+        assert_eq!(producer.push(0x3), Err(0x3));
+
+        assert_eq!(consumer.len(), 2);
+        assert_eq!(consumer.poll(), Some(0x1));
+        assert!(producer.push(0x3).is_ok());
+        assert_eq!(consumer.poll(), Some(0x2));
+        assert_eq!(consumer.poll(), Some(0x3));
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn isr_ring_counter_wrap() {
+        let mut ring = IsrRing::<u8, 4>::new();
+        ring.head = AtomicUsize::new(usize::MAX - 1);
+        ring.tail = AtomicUsize::new(usize::MAX - 1);
+        let (mut producer, mut consumer) = ring.split();
+
+        for i in 0..4 {
+            assert!(producer.push(i).is_ok());
+        }
+        assert!(producer.is_full());
+        assert_eq!(consumer.poll(), Some(0));
+        assert!(producer.push(4).is_ok());
+        for i in 1..5 {
+            assert_eq!(consumer.poll(), Some(i));
+        }
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn isr_ring_threads() {
+        let mut ring = IsrRing::<u32, 8>::new();
+        let (mut producer, mut consumer) = ring.split();
+
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..1000 {
+                    let mut item = i;
+                    while let Err(back) = producer.push(item) {
+                        item = back;
+                        std::thread::yield_now();
+                    }
+                }
+            });
+
+            let mut expected = 0;
+            while expected < 1000 {
+                if let Some(item) = consumer.poll() {
+                    assert_eq!(item, expected);
+                    expected += 1;
+                }
+            }
+        });
+    }
+}
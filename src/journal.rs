@@ -0,0 +1,156 @@
+//! Журнал последних операций для разбора отказов.
+
+use crate::FrodoRing;
+
+/// Число записей журнала.
+pub(crate) const JOURNAL_LEN: usize = 16;
+
+/// Вид операции, записанной в журнал.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalOp {
+    /// Элемент положен в ячейку.
+    Push,
+    /// Элемент изъят из ячейки.
+    Take,
+    /// Несколько элементов положены подряд, начиная с ячейки; содержит их число.
+    PushMany(usize),
+    /// Несколько элементов изъяты, начиная с ячейки (пустые ячейки между ними пропускаются); содержит их число.
+    TakeMany(usize),
+    /// Элемент из ячейки перенесён в начало очереди.
+    MoveToFront,
+    /// Элемент из ячейки перенесён в конец очереди.
+    MoveToBack,
+    /// Проведено сжатие.
+    Compact,
+    /// Очередь очищена.
+    Clear,
+}
+
+/// Запись журнала: операция, наивная позиция ячейки и состояние очереди после операции.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// Вид операции.
+    pub op: JournalOp,
+    /// Наивная позиция ячейки, к которой относится операция (для сжатия и очистки - 0).
+    pub naive_pos: usize,
+    /// Начало очереди после операции.
+    pub head: usize,
+    /// Используемая ёмкость после операции.
+    pub cap: usize,
+}
+
+/// Кольцо последних записей журнала.
+pub(crate) struct Journal {
+    entries: [JournalEntry; JOURNAL_LEN],
+    /// Общее число записанных операций (с переполнением через ноль).
+    written: usize,
+}
+
+impl Journal {
+    pub(crate) const fn new() -> Self {
+        Self {
+            entries: [JournalEntry {
+                op: JournalOp::Clear,
+                naive_pos: 0,
+                head: 0,
+                cap: 0,
+            }; JOURNAL_LEN],
+            written: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает последние (до 16) операции над очередью, от старой к новой.
+    ///
+    /// Не выделяет память и не берёт блокировок, поэтому журнал можно выгрузить из обработчика отказа
+    /// (например, HardFault), чтобы восстановить последовательность действий, приведшую к ошибке.
+    pub fn journal(&self) -> impl Iterator<Item = JournalEntry> + '_ {
+        let written = self.journal.written;
+        let len = written.min(JOURNAL_LEN);
+        (written - len..written).map(|i| self.journal.entries[i % JOURNAL_LEN])
+    }
+
+    /// Записывает операцию над ячейкой с наивной позицией `naive_pos` в журнал.
+    pub(crate) fn record(&mut self, op: JournalOp, naive_pos: usize) {
+        let journal = &mut self.journal;
+        journal.entries[journal.written % JOURNAL_LEN] = JournalEntry {
+            op,
+            naive_pos,
+            head: self.head,
+            cap: self.cap,
+        };
+        journal.written = journal.written.wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal() {
+        let mut ring = FrodoRing::<u8, 3>::new();
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.pick(), Some(0x1));
+
+        let ops = ring
+            .journal()
+            .map(|e| (e.op, e.naive_pos, e.head, e.cap))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ops,
+            [
+                (JournalOp::Push, 0, 0, 1),
+                (JournalOp::Push, 1, 0, 2),
+                (JournalOp::Push, 2, 0, 3),
+                (JournalOp::Take, 1, 0, 3),
+                (JournalOp::Compact, 0, 0, 2),
+                (JournalOp::Push, 2, 0, 3),
+                (JournalOp::Take, 0, 1, 2),
+            ]
+        );
+
+        for _ in 0..20 {
+            ring.clear();
+        }
+        assert_eq!(ring.journal().count(), 16);
+        assert!(ring.journal().all(|e| e.op == JournalOp::Clear));
+    }
+
+    #[test]
+    fn journal_bulk() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+        assert_eq!(ring.push_slice(&[0x1, 0x2, 0x3]), 3);
+        ring.begin();
+        assert!(ring.push(0x4).is_ok());
+        assert!(ring.push(0x5).is_ok());
+        ring.commit();
+        assert!(ring.move_to_front(4));
+        assert_eq!(ring.remove_range(1..3), 2);
+
+        let mut out = [0; 4];
+        assert_eq!(ring.pick_slice(&mut out), 3);
+        assert_eq!(out[..3], [0x5, 0x3, 0x4]);
+
+        let ops = ring
+            .journal()
+            .map(|e| (e.op, e.naive_pos, e.head, e.cap))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ops,
+            [
+                (JournalOp::PushMany(3), 0, 0, 3),
+                (JournalOp::PushMany(2), 3, 0, 5),
+                (JournalOp::MoveToFront, 4, 7, 5),
+                (JournalOp::TakeMany(2), 1, 7, 5),
+                (JournalOp::TakeMany(1), 0, 2, 2),
+                (JournalOp::TakeMany(2), 0, 4, 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Очередь с индексом по небольшому целочисленному ключу.
+
+use crate::FrodoRing;
+
+/// Пустое звено цепочки индекса.
+const NIL: usize = usize::MAX;
+
+/// Небольшой целочисленный ключ элемента (например, номер канала DMA или конечной точки USB).
+pub trait SmallKey: Copy + Eq {
+    /// Возвращает значение ключа для распределения по корзинам индекса.
+    fn index(self) -> usize;
+}
+
+macro_rules! small_key {
+    ($($ty:ty),*) => {
+        $(
+            impl SmallKey for $ty {
+                fn index(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+small_key!(u8, u16);
+
+/// Очередь, каждый элемент которой снабжён ключом `K`; поиск по ключу идёт по индексу, а не перебором.
+///
+/// Индекс - цепочки по корзинам `key % N`, связанные через реальные позиции ячеек; при изъятии элементы
+/// не перемещаются, так что индекс обновляется за длину цепочки, и только после сжатия перестраивается целиком.
+///
+/// Дополнительно элемент может нести ранг `P` (например, дедлайн или `Reverse` приоритета):
+/// `pick_first_ranked()` выдаёт элемент с наименьшим рангом, а оба индекса согласуются автоматически.
+pub struct FrodoKeyedRing<K, T, const N: usize, P = ()> {
+    ring: FrodoRing<(K, P, T), N>,
+    /// Первая реальная позиция цепочки каждой корзины.
+    buckets: [usize; N],
+    /// Следующая реальная позиция в цепочке.
+    next: [usize; N],
+    /// Реальная позиция самого старого элемента с наименьшим рангом или `NIL`, если его нужно найти заново.
+    first_ranked: usize,
+    /// Число сжатий очереди на момент последнего построения индекса.
+    compactions: u32,
+}
+
+impl<K: SmallKey, T, const N: usize, P: Ord> Default for FrodoKeyedRing<K, T, N, P> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            buckets: [NIL; N],
+            next: [NIL; N],
+            first_ranked: NIL,
+            compactions: 0,
+        }
+    }
+}
+
+impl<K: SmallKey, T, const N: usize, P: Ord + Default> FrodoKeyedRing<K, T, N, P> {
+    /// Кладёт элемент с ключом `key` и рангом по умолчанию в очередь.
+    pub fn push(&mut self, key: K, item: T) -> Result<(), T> {
+        self.push_ranked(key, P::default(), item)
+    }
+
+    /// Заменяет на месте первый элемент с ключом `key`, а если такого нет, кладёт элемент в очередь.
+    ///
+    /// Заменённый элемент сохраняет своё место в очереди и ранг. Возвращает прежнее значение;
+    /// если элемента с ключом не было, а очередь заполнена, новый элемент возвращается обратно.
+    pub fn insert_or_replace(&mut self, key: K, item: T) -> Result<Option<T>, T> {
+        let Some(naive_pos) = self.position_by_key(key) else {
+            return self.push(key, item).map(|()| None);
+        };
+
+        let real_pos = self.ring.real_pos(naive_pos as usize);
+        let (_, _, old) = unsafe { self.ring.buffer[real_pos].assume_init_mut() };
+        Ok(Some(core::mem::replace(old, item)))
+    }
+}
+
+impl<K: SmallKey, T, const N: usize, P: Ord> FrodoKeyedRing<K, T, N, P> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Возвращает очередь с элементами и их ключами.
+    pub fn ring(&self) -> &FrodoRing<(K, P, T), N> {
+        &self.ring
+    }
+
+    /// Кладёт элемент с ключом `key` и рангом `rank` в очередь.
+    pub fn push_ranked(&mut self, key: K, rank: P, item: T) -> Result<(), T> {
+        self.ring
+            .push((key, rank, item))
+            .map_err(|(_, _, item)| item)?;
+
+        if self.ring.compactions != self.compactions {
+            self.rebuild();
+            return Ok(());
+        }
+
+        let real_pos = self.ring.real_pos(self.ring.cap - 1);
+        self.link(key, real_pos);
+        if self.first_ranked != NIL && self.rank_at(real_pos) < self.rank_at(self.first_ranked) {
+            self.first_ranked = real_pos;
+        }
+        Ok(())
+    }
+
+    /// Находит наивную позицию первого элемента с ключом `key`.
+    pub fn position_by_key(&self, key: K) -> Option<isize> {
+        let mut first: Option<usize> = None;
+        let mut real_pos = self.buckets[key.index() % N];
+        while real_pos != NIL {
+            let naive_pos = (real_pos + N - self.ring.head) % N;
+            if self.key_at(real_pos) == key && first.is_none_or(|first| naive_pos < first) {
+                first = Some(naive_pos);
+            }
+            real_pos = self.next[real_pos];
+        }
+
+        first.map(|naive_pos| naive_pos as isize)
+    }
+
+    /// Получает первый элемент с ключом `key`.
+    pub fn get_by_key(&self, key: K) -> Option<&T> {
+        let naive_pos = self.position_by_key(key)?;
+        self.ring.at(naive_pos).map(|(_, _, item)| item)
+    }
+
+    /// Изымает первый элемент с ключом `key`.
+    pub fn remove_by_key(&mut self, key: K) -> Option<T> {
+        let naive_pos = self.position_by_key(key)?;
+        self.remove_at(naive_pos).map(|(_, item)| item)
+    }
+
+    /// Находит наивную позицию самого старого элемента с наименьшим рангом.
+    pub fn position_first_ranked(&mut self) -> Option<isize> {
+        if self.first_ranked == NIL {
+            for naive_pos in 0..self.ring.cap {
+                let real_pos = self.ring.real_pos(naive_pos);
+                if self.ring.occupied[real_pos]
+                    && (self.first_ranked == NIL
+                        || self.rank_at(real_pos) < self.rank_at(self.first_ranked))
+                {
+                    self.first_ranked = real_pos;
+                }
+            }
+        }
+
+        match self.first_ranked {
+            NIL => None,
+            real_pos => Some(((real_pos + N - self.ring.head) % N) as isize),
+        }
+    }
+
+    /// Отдаёт самый старый элемент с наименьшим рангом вместе с его ключом, изымая его из очереди.
+    pub fn pick_first_ranked(&mut self) -> Option<(K, T)> {
+        let naive_pos = self.position_first_ranked()?;
+        self.remove_at(naive_pos)
+    }
+
+    /// Изымает элемент по ячейке (наивной позиции) вместе с его ключом.
+    pub fn remove_at(&mut self, naive_pos: isize) -> Option<(K, T)> {
+        let real_pos = self.ring.checked_real_pos(naive_pos)?;
+        if !self.ring.occupied[real_pos] {
+            return None;
+        }
+
+        self.unlink(real_pos);
+        if self.first_ranked == real_pos {
+            self.first_ranked = NIL;
+        }
+        self.ring
+            .remove_at(naive_pos)
+            .map(|(key, _, item)| (key, item))
+    }
+
+    /// Отдаёт первый элемент вместе с его ключом, изымая его из очереди.
+    pub fn pick(&mut self) -> Option<(K, T)> {
+        self.remove_at(0)
+    }
+
+    fn key_at(&self, real_pos: usize) -> K {
+        unsafe { self.ring.buffer[real_pos].assume_init_ref().0 }
+    }
+
+    fn rank_at(&self, real_pos: usize) -> &P {
+        unsafe { &self.ring.buffer[real_pos].assume_init_ref().1 }
+    }
+
+    fn link(&mut self, key: K, real_pos: usize) {
+        let bucket = key.index() % N;
+        self.next[real_pos] = self.buckets[bucket];
+        self.buckets[bucket] = real_pos;
+    }
+
+    fn unlink(&mut self, real_pos: usize) {
+        let bucket = self.key_at(real_pos).index() % N;
+        if self.buckets[bucket] == real_pos {
+            self.buckets[bucket] = self.next[real_pos];
+            return;
+        }
+
+        let mut prev = self.buckets[bucket];
+        while self.next[prev] != real_pos {
+            prev = self.next[prev];
+        }
+        self.next[prev] = self.next[real_pos];
+    }
+
+    /// Перестраивает индекс после сжатия очереди.
+    fn rebuild(&mut self) {
+        self.buckets = [NIL; N];
+        self.first_ranked = NIL;
+        for naive_pos in 0..self.ring.cap {
+            let real_pos = self.ring.real_pos(naive_pos);
+            if self.ring.occupied[real_pos] {
+                self.link(self.key_at(real_pos), real_pos);
+            }
+        }
+        self.compactions = self.ring.compactions;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyed_ring() {
+        let mut ring = FrodoKeyedRing::<u8, &str, 4>::new();
+        assert!(ring.push(3, "ch3").is_ok());
+        assert!(ring.push(7, "ch7").is_ok());
+        assert!(ring.push(11, "ch11").is_ok());
+        assert!(ring.push(7, "ch7'").is_ok());
+        assert_eq!(ring.push(1, "ch1"), Err("ch1"));
+
+        assert_eq!(ring.position_by_key(7), Some(1));
+        assert_eq!(ring.get_by_key(11), Some(&"ch11"));
+        assert_eq!(ring.position_by_key(5), None);
+
+        assert_eq!(ring.remove_by_key(7), Some("ch7"));
+        assert_eq!(ring.position_by_key(7), Some(3));
+        assert_eq!(ring.pick(), Some((3, "ch3")));
+
+        // Сжатие перемещает элементы, индекс перестраивается
+        assert!(ring.push(1, "ch1").is_ok());
+        assert!(ring.push(2, "ch2").is_ok());
+        assert_eq!(ring.position_by_key(11), Some(0));
+        assert_eq!(ring.position_by_key(7), Some(1));
+        assert_eq!(ring.remove_by_key(2), Some("ch2"));
+        assert_eq!(ring.get_by_key(1), Some(&"ch1"));
+    }
+
+    #[test]
+    fn insert_or_replace() {
+        let mut ring = FrodoKeyedRing::<u8, u32, 3>::new();
+        assert_eq!(ring.insert_or_replace(1, 10), Ok(None));
+        assert_eq!(ring.insert_or_replace(2, 20), Ok(None));
+        assert_eq!(ring.insert_or_replace(1, 11), Ok(Some(10)));
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.position_by_key(1), Some(0));
+
+        assert_eq!(ring.insert_or_replace(3, 30), Ok(None));
+        assert_eq!(ring.insert_or_replace(4, 40), Err(40));
+        assert_eq!(ring.insert_or_replace(2, 21), Ok(Some(20)));
+        assert_eq!(ring.pick(), Some((1, 11)));
+        assert_eq!(ring.pick(), Some((2, 21)));
+    }
+
+    #[test]
+    fn keyed_ring_ranked() {
+        // Ранг - дедлайн: раньше дедлайн, раньше выдача
+        let mut ring = FrodoKeyedRing::<u16, char, 4, u32>::new();
+        assert!(ring.push_ranked(1, 30, 'a').is_ok());
+        assert!(ring.push_ranked(2, 10, 'b').is_ok());
+        assert!(ring.push_ranked(3, 20, 'c').is_ok());
+        assert!(ring.push_ranked(4, 10, 'd').is_ok());
+
+        assert_eq!(ring.pick_first_ranked(), Some((2, 'b')));
+        assert_eq!(ring.position_by_key(2), None);
+        assert_eq!(ring.remove_by_key(4), Some('d'));
+        assert_eq!(ring.pick_first_ranked(), Some((3, 'c')));
+
+        assert!(ring.push_ranked(5, 5, 'e').is_ok());
+        assert!(ring.push_ranked(6, 40, 'f').is_ok());
+        assert_eq!(ring.position_by_key(6), Some(2));
+        assert_eq!(ring.pick_first_ranked(), Some((5, 'e')));
+        assert_eq!(ring.pick_first_ranked(), Some((1, 'a')));
+        assert_eq!(ring.pick(), Some((6, 'f')));
+        assert!(ring.is_empty());
+    }
+}
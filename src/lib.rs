@@ -1,6 +1,129 @@
 //! Предоставляет реализацию очереди FIFO на кольцевом буфере, не использующем аллокации.
 
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::cmp::Ordering;
 use core::mem::MaybeUninit;
+use core::ops::{Bound, ControlFlow, RangeBounds};
+
+mod async_ring;
+mod atomic;
+#[cfg(feature = "std")]
+mod blocking;
+mod broadcast;
+mod bulk;
+mod command;
+mod cursor;
+mod decimate;
+mod delta;
+mod edf;
+mod eviction;
+mod ewma;
+mod expire;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod filtered;
+mod fmt_write;
+mod isr;
+#[cfg(feature = "journal")]
+mod journal;
+mod keyed;
+mod limit;
+mod metrics;
+mod mpsc;
+mod niche;
+mod object_pool;
+mod peek_guard;
+mod pinned;
+mod prefetch;
+mod priority;
+mod quantile;
+mod random;
+mod rc;
+mod rcu;
+mod reassembly;
+mod reserve;
+mod ring_pool;
+mod roles;
+mod round_robin;
+mod sample;
+mod seq;
+mod seqlock;
+mod shared;
+mod small;
+mod snapshot;
+mod spare;
+mod split;
+mod static_ring;
+#[cfg(feature = "stats")]
+mod stats;
+mod sum;
+#[cfg(feature = "alloc")]
+mod tiered;
+mod timed;
+mod transfer;
+mod validate;
+mod var_ring;
+#[cfg(feature = "std")]
+mod vec_deque;
+mod view;
+mod watch;
+mod watermark;
+mod window;
+
+pub use async_ring::{FrodoAsyncRing, FrodoSelect};
+pub use atomic::{FrodoAtomicConsumer, FrodoAtomicProducer, FrodoAtomicRing};
+#[cfg(feature = "std")]
+pub use blocking::FrodoBlockingRing;
+pub use broadcast::{FrodoBroadcast, LagPolicy, Lagged, ReaderId};
+pub use command::{CommandHandle, CommandState, FrodoCommandRing};
+pub use cursor::FrodoCursor;
+pub use delta::FrodoDeltaRing;
+pub use edf::FrodoEdf;
+pub use ewma::FrodoEwma;
+pub use filtered::FrodoRingFiltered;
+pub use isr::{IsrConsumer, IsrProducer, IsrRing};
+#[cfg(feature = "journal")]
+pub use journal::{JournalEntry, JournalOp};
+pub use keyed::{FrodoKeyedRing, SmallKey};
+pub use limit::LimitError;
+#[cfg(feature = "std")]
+pub use metrics::metrics_prometheus_header;
+pub use mpsc::{FrodoMpscRing, MpscConsumer, MpscProducer};
+pub use niche::{FrodoNicheRing, Sentinel};
+pub use object_pool::{FrodoObjectPool, FrodoPooled};
+pub use peek_guard::FrodoPeekGuard;
+pub use pinned::FrodoPinRing;
+pub use priority::FrodoPriority;
+pub use rc::{FrodoRcRing, FrodoRef};
+pub use rcu::{FrodoRcuReader, FrodoRcuRing, FrodoRcuView, FrodoRcuWriter};
+pub use reassembly::{Framing, Reassembler};
+pub use reserve::FrodoSlot;
+pub use ring_pool::{FrodoRingHandle, FrodoRingPool};
+pub use roles::{ConsumerToken, FrodoRoleRing, ProducerToken};
+pub use round_robin::FrodoRoundRobin;
+pub use sample::{FrodoDecimator, FrodoReservoir};
+pub use seq::FrodoSeqRing;
+pub use seqlock::{FrodoSeqlock, FrodoSeqlockReader, FrodoSeqlockWriter};
+pub use shared::{FrodoShared, FrodoSharedGuard, HardwareLock, SpinLock};
+pub use small::FrodoSmallRing;
+pub use snapshot::Pod;
+pub use split::{FrodoRingSplit, FrodoRingSplitMut};
+pub use sum::{FrodoSumRing, Summable};
+#[cfg(feature = "alloc")]
+pub use tiered::FrodoTieredRing;
+pub use timed::{FrodoTimedRing, TimeSource};
+pub use transfer::TransferError;
+pub use validate::ValidationError;
+pub use var_ring::{FrodoRecord, FrodoVarRing};
+pub use view::FrodoRingView;
+pub use watch::FrodoWatch;
+pub use watermark::Watermark;
+use watermark::Watermarks;
+pub use window::FrodoWindow;
 
 /// Кольцевая очередь с порядком FIFO и не использующая аллокации.
 ///
@@ -20,10 +143,93 @@ pub struct FrodoRing<T, const N: usize> {
     ///
     /// В очереди всегда будут элементы `self.get(0)` и `self.get(self.used() - 1)`, если cap > 0.
     cap: usize,
+    /// Реальная позиция первой ячейки открытой транзакции, если транзакция начата.
+    ///
+    /// Отложенные элементы лежат в ячейках сразу за используемой ёмкостью и не помечены занятыми до `commit()`.
+    staging: Option<usize>,
+    /// Число отложенных элементов открытой транзакции.
+    staged: usize,
+    /// Логическое ограничение числа элементов в очереди (не больше N).
+    limit: usize,
+    /// Ячейки внутри используемой ёмкости, зарезервированные через `reserve()` и ещё не заполненные.
+    reserved: [bool; N],
+    /// Число незавершённых резервирований.
+    reservations: usize,
+    /// Число закреплений ячеек извне (например, разделяемыми ссылками); пока оно не ноль, элементы не перемещаются.
+    pins: usize,
+    /// Пороги заполненности с обработчиком их пересечения.
+    watermarks: Option<Watermarks>,
+    /// Число отказов в свободной ячейке.
+    rejections: u32,
+    /// Число проведённых сжатий.
+    compactions: u32,
+    /// Наибольшая глубина очереди с момента создания или `reset_depth_max()`.
+    depth_max: usize,
+    /// Извлекатель приоритета для вытеснения при заполненности (`set_eviction_priority()`).
+    eviction: Option<fn(&T) -> u8>,
+    /// Гистограмма глубины очереди, наблюдаемой при `push()` и `pick()`.
+    #[cfg(feature = "stats")]
+    depth_histogram: [u32; stats::DEPTH_BUCKETS],
+    /// Журнал последних операций.
+    #[cfg(feature = "journal")]
+    journal: journal::Journal,
+}
+
+impl<T: Clone, const N: usize> FrodoRing<T, N> {
+    /// Создаёт заполненную очередь из `N` копий `value`.
+    pub fn fill(value: T) -> Self {
+        Self::from_fn(|_| value.clone())
+    }
+}
+
+/// Счётчик идентификаторов экземпляров, которыми помечаются выдаваемые ими дескрипторы.
+static NEXT_INSTANCE_ID: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+/// Выдаёт новый идентификатор экземпляра, чтобы отличать его дескрипторы от дескрипторов других экземпляров.
+fn next_instance_id() -> usize {
+    NEXT_INSTANCE_ID.fetch_add(1, core::sync::atomic::Ordering::Relaxed)
 }
 
-impl<T: std::fmt::Debug, const N: usize> std::fmt::Debug for FrodoRing<T, N> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// Наибольшая ёмкость, при которой компактный вывод `{:#?}` перечисляет значения элементов.
+const DEBUG_VALUES_MAX: usize = 16;
+
+impl<T: core::fmt::Debug, const N: usize> core::fmt::Debug for FrodoRing<T, N> {
+    /// `{:?}` выводит каждую ячейку на отдельной строке, а `{:#?}` - компактную карту занятости ячеек
+    /// (`#` - занята, `r` - зарезервирована, `.` - пуста) и значения элементов только для небольших N.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            write!(
+                f,
+                "head={} cap={} len={} [",
+                self.head,
+                self.cap,
+                self.len()
+            )?;
+            for i in 0..N {
+                let cell = if self.occupied[i] {
+                    '#'
+                } else if self.reserved[i] {
+                    'r'
+                } else {
+                    '.'
+                };
+                write!(f, "{cell}")?;
+            }
+            write!(f, "]")?;
+
+            if N <= DEBUG_VALUES_MAX {
+                write!(f, " [")?;
+                for (i, item) in self.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item:?}")?;
+                }
+                write!(f, "]")?;
+            }
+            return Ok(());
+        }
+
         writeln!(
             f,
             "Ring: occupied = {}, head = {}, capacity = {}",
@@ -47,16 +253,53 @@ impl<T: std::fmt::Debug, const N: usize> std::fmt::Debug for FrodoRing<T, N> {
 
 impl<T, const N: usize> Default for FrodoRing<T, N> {
     fn default() -> Self {
+        let () = Self::NONZERO;
+
         Self {
             buffer: unsafe { MaybeUninit::uninit().assume_init() },
             occupied: [false; N],
             head: 0,
             cap: 0,
+            staging: None,
+            staged: 0,
+            limit: N,
+            reserved: [false; N],
+            reservations: 0,
+            pins: 0,
+            watermarks: None,
+            rejections: 0,
+            compactions: 0,
+            depth_max: 0,
+            eviction: None,
+            #[cfg(feature = "stats")]
+            depth_histogram: [0; stats::DEPTH_BUCKETS],
+            #[cfg(feature = "journal")]
+            journal: journal::Journal::new(),
         }
     }
 }
 
+impl<T, const N: usize> Drop for FrodoRing<T, N> {
+    fn drop(&mut self) {
+        self.watermarks = None;
+        self.clear();
+    }
+}
+
 impl<T, const N: usize> FrodoRing<T, N> {
+    /// Очередь нулевой ёмкости отвергается при компиляции.
+    const NONZERO: () = assert!(N > 0, "FrodoRing capacity N must be greater than zero");
+
+    /// Возвращает размер, занимаемый очередью в памяти, в байтах.
+    pub const fn footprint() -> usize {
+        core::mem::size_of::<Self>()
+    }
+
+    /// Возвращает выравнивание очереди в памяти.
+    pub const fn alignment() -> usize {
+        core::mem::align_of::<Self>()
+    }
+
     /// Возвращает позицию N-ного элемента в кольце.
     fn real_pos(&self, naive_pos: usize) -> usize {
         (self.head + naive_pos) % N
@@ -64,7 +307,7 @@ impl<T, const N: usize> FrodoRing<T, N> {
 
     /// Можно также передавать позицию с конца; например, `1` - это последний элемент.
     fn neg_pos(&self, naive_pos: usize) -> usize {
-        (self.head + N - naive_pos) % N
+        (self.head + self.cap + N - naive_pos) % N
     }
 
     /// Создаёт новую кольцевую очередь.
@@ -72,11 +315,43 @@ impl<T, const N: usize> FrodoRing<T, N> {
         Self::default()
     }
 
+    /// Создаёт заполненную очередь, `i`-й элемент которой равен `f(i)`.
+    pub fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self {
+        let mut ring = Self::new();
+        for i in 0..N {
+            ring.buffer[i].write(f(i));
+            ring.occupied[i] = true;
+            ring.cap += 1;
+        }
+        ring.note_depth();
+        ring
+    }
+
     /// Возвращает использованное число ячеек кольцевой очереди.
     pub fn used(&self) -> usize {
         self.cap
     }
 
+    /// Возвращает реальную позицию (индекс ячейки буфера) начала очереди.
+    pub fn head(&self) -> usize {
+        self.head
+    }
+
+    /// Переводит наивную позицию (в том числе отрицательную) в реальную, если она внутри используемой ёмкости.
+    pub fn naive_to_real(&self, naive_pos: isize) -> Option<usize> {
+        self.checked_real_pos(naive_pos)
+    }
+
+    /// Переводит реальную позицию в наивную, если ячейка внутри используемой ёмкости.
+    pub fn real_to_naive(&self, real_pos: usize) -> Option<usize> {
+        if real_pos >= N {
+            return None;
+        }
+
+        let naive_pos = (real_pos + N - self.head) % N;
+        (naive_pos < self.cap).then_some(naive_pos)
+    }
+
     /// Возвращает число элементов, находящихся в очереди.
     pub fn len(&self) -> usize {
         self.occupied.iter().filter(|v| **v).count()
@@ -84,7 +359,47 @@ impl<T, const N: usize> FrodoRing<T, N> {
 
     /// Сообщает, есть ли в очереди элементы.
     pub fn is_empty(&self) -> bool {
-        self.cap == 0
+        self.cap == 0 || (self.reservations > 0 && self.len() == 0)
+    }
+
+    /// Сообщает, что следующий `push()` заведомо не найдёт места, даже после сжатия пропусков.
+    ///
+    /// Учитывает ограничение ёмкости, открытую транзакцию и закрепление элементов.
+    pub fn is_full(&self) -> bool {
+        let len = self.len();
+        let Some(stage_head) = self.staging else {
+            return (self.cap >= self.limit && len + self.reservations >= self.limit)
+                || (self.cap == N && (self.is_pinned() || len == N));
+        };
+
+        let span = if self.cap == 0 {
+            self.staged
+        } else {
+            self.stage_offset(stage_head) + self.staged
+        };
+        self.limited_len() >= self.limit
+            || (span == N && (self.is_pinned() || len + self.staged == N))
+    }
+
+    /// Удаляет все элементы очереди, включая отложенные в открытой транзакции, и отменяет резервирования.
+    pub fn clear(&mut self) {
+        self.rollback();
+
+        self.reserved = [false; N];
+        self.reservations = 0;
+
+        for i in 0..N {
+            if self.occupied[i] {
+                self.occupied[i] = false;
+                unsafe { self.buffer[i].assume_init_drop() };
+            }
+        }
+
+        self.head = 0;
+        self.cap = 0;
+        #[cfg(feature = "journal")]
+        self.record(journal::JournalOp::Clear, 0);
+        self.check_watermarks();
     }
 
     /// Получает элемент по ячейке (наивной позиции).
@@ -151,268 +466,1146 @@ impl<T, const N: usize> FrodoRing<T, N> {
         }
     }
 
-    /// Получает наивную позицию (ячейку) элемента, отвечающего условию.
-    ///
-    /// Чтобы получить сам элемент, используйте `ring.at(naive_pos)`.
-    pub fn position<F: Fn(&T) -> bool>(&self, f: F) -> Option<isize> {
-        let mut real_pos = self.head;
-        let last_pos = self.neg_pos(1);
+    /// Создаёт итератор по очереди, выдающий элементы вместе с их позицией в очереди (как у `get`).
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (usize, &T)> {
+        (0..self.cap)
+            .filter_map(|naive_pos| self.at(naive_pos as isize))
+            .enumerate()
+    }
 
-        while real_pos <= last_pos {
-            if self.occupied[real_pos] && f(unsafe { self.buffer[real_pos].assume_init_ref() }) {
-                return Some(real_pos as isize);
+    /// Возвращает ссылки на первые `K` элементов в порядке очереди.
+    ///
+    /// Если элементов в очереди меньше `K`, оставшиеся позиции заполняются `None`.
+    pub fn peek_n<const K: usize>(&self) -> [Option<&T>; K] {
+        let mut out = [None; K];
+        let mut filled = 0usize;
+
+        for naive_pos in 0..self.cap {
+            if filled == K {
+                break;
+            }
+            if let Some(item) = self.at(naive_pos as isize) {
+                out[filled] = Some(item);
+                filled += 1;
             }
-            real_pos = (real_pos + 1) % N;
         }
 
-        None
+        out
     }
 
-    /// Кладёт элемент в очередь.
+    /// Возвращает ссылки на `K` последних добавленных элементов; самый новый элемент - последний.
     ///
-    /// В случае, если число использованных очередью ячеек равно N, но при этом хотя бы одна из них не занята,
-    /// очередь проводит операцию сжатия (`O(n)`) с перемещением элементов в памяти.
-    pub fn push(&mut self, item: T) -> Result<(), T> {
-        let real_pos = if self.cap == N {
-            if self.occupied.iter().all(|o| *o) {
-                return Err(item);
-            } else if let Some(tail) = self.compact() {
-                tail
-            } else {
-                return Err(item);
+    /// Если элементов в очереди меньше `K`, начальные позиции заполняются `None`.
+    pub fn last_n<const K: usize>(&self) -> [Option<&T>; K] {
+        let mut out = [None; K];
+        let mut filled = 0usize;
+
+        for naive_pos in (0..self.cap).rev() {
+            if filled == K {
+                break;
             }
-        } else {
-            self.real_pos(self.cap)
-        };
+            if let Some(item) = self.at(naive_pos as isize) {
+                out[K - 1 - filled] = Some(item);
+                filled += 1;
+            }
+        }
 
-        self.buffer[real_pos].write(item);
-        self.occupied[real_pos] = true;
-        self.cap += 1;
-        Ok(())
+        out
     }
 
-    /// Отдаёт первый элемент, изымая его из очереди.
-    pub fn pick(&mut self) -> Option<T> {
-        self.remove_at(0)
+    /// Применяет `f` к каждому элементу в порядке очереди.
+    pub fn map_in_place<F: FnMut(&mut T)>(&mut self, mut f: F) {
+        for naive_pos in 0..self.cap {
+            let real_pos = self.real_pos(naive_pos);
+            if self.occupied[real_pos] {
+                f(unsafe { self.buffer[real_pos].assume_init_mut() });
+            }
+        }
     }
 
-    /// Удаляет содержимое ячейки, находящейся по наивной позиции, и возвращает его.
-    pub fn remove_at(&mut self, naive_pos: isize) -> Option<T> {
-        if self.cap == 0 || naive_pos >= self.cap as isize || naive_pos < -(self.cap as isize) {
-            return None;
-        }
+    /// Возвращает число элементов, отвечающих условию.
+    pub fn count_matching<F: Fn(&T) -> bool>(&self, f: F) -> usize {
+        self.iter().filter(|el| f(el)).count()
+    }
 
-        let real_pos = if naive_pos >= 0 {
-            self.real_pos(naive_pos as usize)
-        } else {
-            self.neg_pos((-naive_pos) as usize)
-        };
+    /// Сообщает, есть ли в очереди хотя бы один элемент, отвечающий условию.
+    pub fn any<F: Fn(&T) -> bool>(&self, f: F) -> bool {
+        self.iter().any(f)
+    }
 
-        if self.occupied[real_pos] {
-            self.occupied[real_pos] = false;
-
-            if real_pos == self.head {
-                loop {
-                    self.head = (self.head + 1) % N;
-                    self.cap -= 1;
-                    if self.occupied[self.head] || self.cap == 0 {
-                        break;
-                    }
-                }
-            } else if real_pos == self.neg_pos(1) {
-                loop {
-                    if self.occupied[self.real_pos(self.cap - 1)] || self.cap == 1 {
-                        break;
-                    }
-                    self.cap -= 1;
+    /// Сообщает, отвечают ли условию все элементы очереди (для пустой очереди - `true`).
+    pub fn all<F: Fn(&T) -> bool>(&self, f: F) -> bool {
+        self.iter().all(f)
+    }
+
+    /// Находит `k`-й по порядку `compare` элемент (начиная с нуля), не сортируя очередь.
+    ///
+    /// Порядок элементов в очереди не меняется, а дополнительной памяти не требуется: для каждого кандидата
+    /// подсчитывается число меньших и равных ему элементов, что занимает O(n²) сравнений.
+    pub fn select_nth_unstable_by<F: FnMut(&T, &T) -> Ordering>(
+        &self,
+        k: usize,
+        mut compare: F,
+    ) -> Option<&T> {
+        self.iter().find(|candidate| {
+            let (mut less, mut equal) = (0usize, 0usize);
+            for item in self.iter() {
+                match compare(item, candidate) {
+                    Ordering::Less => less += 1,
+                    Ordering::Equal => equal += 1,
+                    Ordering::Greater => {}
                 }
             }
-
-            Some(unsafe { self.buffer[real_pos].assume_init_read() })
-        } else {
-            None
-        }
+            less <= k && k < less + equal
+        })
     }
 
-    /// Удаляет элемент из очереди.
-    pub fn remove(&mut self, pos: usize) -> Option<T> {
-        if pos >= self.cap || self.cap == 0 {
+    /// Стабильно переупорядочивает очередь так, чтобы элементы, отвечающие условию, шли первыми.
+    ///
+    /// Относительный порядок внутри каждой группы сохраняется, пустые ячейки остаются на своих местах.
+    /// Дополнительной памяти не требуется, но в худшем случае выполняется O(n²) перемещений.
+    /// Возвращает позицию в очереди (как у `get`) первого элемента, не отвечающего условию,
+    /// или `None`, если элементы закреплены и не могут перемещаться.
+    pub fn partition_in_place<F: FnMut(&T) -> bool>(&mut self, mut predicate: F) -> Option<usize> {
+        if self.is_pinned() {
             return None;
         }
 
-        let mut cntr = 0usize;
-        let mut real_pos = self.head;
-        let max_cntr = self.len();
+        // Занятые ячейки до `first_rest` уже отвечают условию; каждый найденный после них подходящий элемент
+        // сдвигается в начало неподходящих вращением занятых ячеек на одну позицию, без памяти на стеке
+        let mut split = 0usize;
+        let mut first_rest = None;
 
-        while cntr < max_cntr {
-            if self.occupied[real_pos] {
-                if cntr == pos {
-                    self.occupied[real_pos] = false;
-
-                    if real_pos == self.head {
-                        loop {
-                            self.head = (self.head + 1) % N;
-                            self.cap -= 1;
-                            if self.occupied[self.head] || self.cap == 0 {
-                                break;
-                            }
-                        }
-                    } else if real_pos == self.neg_pos(1) {
-                        loop {
-                            if self.occupied[self.real_pos(self.cap - 1)] || self.cap == 1 {
-                                break;
-                            }
-                            self.cap -= 1;
-                        }
-                    }
+        for naive_pos in 0..self.cap {
+            let real_pos = self.real_pos(naive_pos);
+            if !self.occupied[real_pos] {
+                continue;
+            }
+            if !predicate(unsafe { self.buffer[real_pos].assume_init_ref() }) {
+                first_rest.get_or_insert(naive_pos);
+                continue;
+            }
 
-                    return Some(unsafe { self.buffer[real_pos].assume_init_read() });
-                } else {
-                    cntr += 1;
+            split += 1;
+            let Some(rest) = first_rest else {
+                continue;
+            };
+
+            let mut item = unsafe { self.buffer[real_pos].assume_init_read() };
+            for pos in rest..naive_pos {
+                let cell = self.real_pos(pos);
+                if self.occupied[cell] {
+                    item = unsafe { core::ptr::replace(self.buffer[cell].as_mut_ptr(), item) };
                 }
             }
-            real_pos = (real_pos + 1) % N;
+            self.buffer[real_pos].write(item);
+
+            first_rest = (rest + 1..=naive_pos).find(|pos| self.occupied[self.real_pos(*pos)]);
         }
 
-        None
+        Some(split)
     }
 
-    /// Ужимает место в буфере, сохраняя порядок расположения элементов.
+    /// Получает наивную позицию (ячейку) элемента, отвечающего условию.
     ///
-    /// Возвращает последнее пустое место (real_pos), куда можно вставить элемент.
+    /// Чтобы получить сам элемент, используйте `ring.at(naive_pos)`.
+    pub fn position<F: Fn(&T) -> bool>(&self, f: F) -> Option<isize> {
+        (0..self.cap)
+            .find(|naive_pos| self.at(*naive_pos as isize).is_some_and(&f))
+            .map(|naive_pos| naive_pos as isize)
+    }
+
+    /// Кладёт элемент в очередь.
     ///
-    /// Важно: метод опирается на то, что первый элемент никогда не будет пустым (`self.real_pos(self.head)`).
-    fn compact(&mut self) -> Option<usize> {
-        assert_eq!(self.cap, N);
+    /// В случае, если число использованных очередью ячеек равно N, но при этом хотя бы одна из них не занята,
+    /// очередь проводит операцию сжатия (`O(n)`) с перемещением элементов в памяти.
+    ///
+    /// Если открыта транзакция (`begin()`), элемент откладывается и станет виден только после `commit()`.
+    ///
+    /// Если задан приоритет вытеснения (`set_eviction_priority()`), при заполненности вытесняет
+    /// самый старый элемент с наименьшим приоритетом, если он ниже приоритета нового элемента.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let result = match (self.push_free(item), self.eviction) {
+            (Err(item), Some(priority)) => self.evict_lighter(item, priority).map(drop),
+            (result, _) => result,
+        };
+        if result.is_err() {
+            self.count_rejection();
+        }
+        result
+    }
 
-        let mut read_pos = 0usize;
-        let mut read_real_pos = self.real_pos(read_pos);
+    /// Кладёт элемент в свободную ячейку без вытеснения; отказ не учитывается.
+    fn push_free(&mut self, item: T) -> Result<(), T> {
+        let Some(real_pos) = self.find_free_cell() else {
+            return Err(item);
+        };
 
-        let mut write_pos = 0usize;
-        let mut write_real_pos = self.real_pos(write_pos);
-        let mut moved = 0usize;
+        self.buffer[real_pos].write(item);
+        self.fill_cell();
+        Ok(())
+    }
 
-        let last_pos = self.cap - 1;
+    /// Кладёт в очередь элемент, конструируя его прямо в ячейке буфера.
+    ///
+    /// Ячейка помечается занятой (или отложенной в открытой транзакции), только если `init` вернул `Ok(())`.
+    ///
+    /// # Safety
+    ///
+    /// Если `init` возвращает `Ok(())`, переданная ему ячейка должна быть полностью инициализирована.
+    pub unsafe fn push_with<E, F: FnOnce(&mut MaybeUninit<T>) -> Result<(), E>>(
+        &mut self,
+        init: F,
+    ) -> Result<(), PushWithError<E>> {
+        let real_pos = self.free_cell().ok_or(PushWithError::Full)?;
+        init(&mut self.buffer[real_pos]).map_err(PushWithError::Init)?;
+        self.fill_cell();
+        Ok(())
+    }
 
-        while read_pos <= last_pos {
-            // Пока элементы совпадают, идём и ищем пропуски
-            if read_pos == write_pos && self.occupied[read_real_pos] {
-                read_pos += 1;
-                read_real_pos = self.real_pos(read_pos);
-                write_pos = read_pos;
-                write_real_pos = read_real_pos;
-                continue;
-            }
+    /// Находит ячейку, в которую можно положить следующий элемент (с учётом транзакции и ограничения).
+    fn free_cell(&mut self) -> Option<usize> {
+        let real_pos = self.find_free_cell();
+        if real_pos.is_none() {
+            self.count_rejection();
+        }
+        real_pos
+    }
 
-            // Если находим пустую ячейку, - перемещаем туда указатель на запись
-            if !self.occupied[read_real_pos] {
-                read_pos += 1;
-                read_real_pos = self.real_pos(read_pos);
-                moved += 1;
-            } else {
-                self.occupied[read_real_pos] = false;
-                self.occupied[write_real_pos] = true;
-                let item = unsafe { self.buffer[read_real_pos].assume_init_read() };
-                self.buffer[write_real_pos].write(item);
+    /// Учитывает отказ положить элемент, возвращённый вызывающему.
+    fn count_rejection(&mut self) {
+        self.rejections = self.rejections.wrapping_add(1);
+    }
 
-                read_pos += 1;
-                read_real_pos = self.real_pos(read_pos);
-                write_pos += 1;
-                write_real_pos = self.real_pos(write_pos);
-            }
-        }
+    /// Сообщает, найдёт ли `find_free_cell()` ячейку после изъятия элемента из занятой ячейки `real_pos`.
+    fn can_refill(&self, real_pos: usize) -> bool {
+        let len = self.len() - 1;
 
-        if moved > 0 {
-            self.cap -= moved;
-            Some(self.real_pos(self.cap))
-        } else {
-            None
+        if let Some(stage_head) = self.staging {
+            // Изъятие не увеличивает охват транзакции, так что оценка по текущему охвату не оптимистична
+            let span = self.stage_offset(stage_head) + self.staged;
+            return len + self.staged + self.reservations < self.limit
+                && !(span == N && self.is_pinned());
         }
+
+        // Изъятие с края очереди сокращает используемую ёмкость, изнутри - оставляет пропуск для сжатия
+        let naive_pos = (real_pos + N - self.head) % N;
+        let interior = naive_pos != 0 && naive_pos != self.cap - 1;
+        len + self.reservations < self.limit && !(self.cap == N && interior && self.is_pinned())
     }
-}
 
-/// Итератор по элементам очереди.
-///
-/// При итерировании пропускает пустые ячейки, выдавая исключительно присутствующие элементы.
-pub struct FrodoRingIterator<'ring, T, const N: usize> {
-    ring: &'ring FrodoRing<T, N>,
-    naive_pos: usize,
-}
+    /// Число ячеек, учитываемых ограничением ёмкости: элементы, отложенные элементы и резервирования.
+    fn limited_len(&self) -> usize {
+        self.len() + self.staged + self.reservations
+    }
 
-impl<'ring, T: std::fmt::Debug, const N: usize> Iterator for FrodoRingIterator<'ring, T, N> {
-    type Item = &'ring T;
+    fn find_free_cell(&mut self) -> Option<usize> {
+        if let Some(stage_head) = self.staging {
+            return self.stage_cell(stage_head);
+        }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            if self.naive_pos == self.ring.cap {
-                return None;
-            }
-            let res = self.ring.at(self.naive_pos as isize);
-            self.naive_pos += 1;
-            if res.is_some() {
-                return res;
+        if self.cap >= self.limit && self.len() + self.reservations >= self.limit {
+            return None;
+        }
+
+        if self.cap == N {
+            if self.is_pinned() || self.occupied.iter().all(|o| *o) {
+                None
+            } else {
+                self.compact()
             }
+        } else {
+            Some(self.real_pos(self.cap))
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_1() {
-        let mut ring = FrodoRing::<u8, 4>::new();
+    /// Помечает заполненной ячейку, ранее найденную `free_cell()`.
+    fn fill_cell(&mut self) {
+        if self.staging.is_some() {
+            self.staged += 1;
+        } else {
+            let real_pos = self.real_pos(self.cap);
+            self.occupied[real_pos] = true;
+            self.cap += 1;
+            #[cfg(feature = "journal")]
+            self.record(journal::JournalOp::Push, self.cap - 1);
+            self.check_watermarks();
+            self.note_depth();
+            #[cfg(feature = "stats")]
+            self.sample_depth();
+        }
+    }
 
-        assert!(ring.push(0x1).is_ok());
-        assert!(ring.push(0x2).is_ok());
-        assert!(ring.push(0x3).is_ok());
-        assert!(ring.push(0x4).is_ok());
+    /// Начинает транзакцию: последующие `push()` откладывают элементы, не делая их видимыми.
+    ///
+    /// Если транзакция уже открыта, ничего не делает.
+    pub fn begin(&mut self) {
+        if self.staging.is_none() {
+            self.staging = Some(self.real_pos(self.cap));
+            self.staged = 0;
+        }
+    }
 
-        assert!(ring.push(0x5).is_err());
+    /// Возвращает число отложенных в открытой транзакции элементов.
+    pub fn staged(&self) -> usize {
+        self.staged
     }
 
-    #[test]
-    fn test_2() {
-        let mut ring = FrodoRing::<u8, 4>::new();
+    /// Делает видимыми все отложенные в транзакции элементы разом и закрывает транзакцию.
+    pub fn commit(&mut self) {
+        let Some(stage_head) = self.staging.take() else {
+            return;
+        };
 
-        assert!(ring.push(0x1).is_ok());
-        assert!(ring.push(0x2).is_ok());
-        assert!(ring.push(0x3).is_ok());
-        assert!(ring.push(0x4).is_ok());
+        if self.staged == 0 {
+            return;
+        }
 
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), Some(&0x2));
-        assert_eq!(ring.at(2), Some(&0x3));
-        assert_eq!(ring.at(3), Some(&0x4));
-        assert_eq!(ring.at(-1), Some(&0x4));
-        assert_eq!(ring.at(-2), Some(&0x3));
-        assert_eq!(ring.at(-3), Some(&0x2));
-        assert_eq!(ring.at(-4), Some(&0x1));
+        if self.cap == 0 {
+            self.head = stage_head;
+            self.cap = self.staged;
+        } else {
+            self.cap = self.stage_offset(stage_head) + self.staged;
+        }
 
-        assert_eq!(ring.at(4), None);
-        assert_eq!(ring.at(-5), None);
+        for i in 0..self.staged {
+            self.occupied[(stage_head + i) % N] = true;
+        }
+        #[cfg(feature = "journal")]
+        self.record(
+            journal::JournalOp::PushMany(self.staged),
+            self.cap - self.staged,
+        );
+        #[cfg(feature = "stats")]
+        self.sample_depths(self.len() - self.staged);
+        self.staged = 0;
+        self.check_watermarks();
+        self.note_depth();
     }
 
-    #[test]
-    fn test_3() {
-        let mut ring = FrodoRing::<u8, 4>::new();
+    /// Отбрасывает все отложенные в транзакции элементы и закрывает транзакцию.
+    pub fn rollback(&mut self) {
+        let Some(stage_head) = self.staging.take() else {
+            return;
+        };
 
-        assert!(ring.push(0x1).is_ok());
-        assert!(ring.push(0x2).is_ok());
-        assert!(ring.push(0x3).is_ok());
-        assert!(ring.push(0x4).is_ok());
+        for i in 0..self.staged {
+            unsafe { self.buffer[(stage_head + i) % N].assume_init_drop() };
+        }
+        self.staged = 0;
+    }
 
-        assert_eq!(ring.remove_at(1), Some(0x2));
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), Some(&0x3));
-        assert_eq!(ring.at(3), Some(&0x4));
+    /// Возвращает расстояние от начала очереди до первой отложенной ячейки.
+    ///
+    /// Имеет смысл только при cap > 0: совпадение позиций означает, что очередь занимает весь буфер.
+    fn stage_offset(&self, stage_head: usize) -> usize {
+        (stage_head + N - self.head - 1) % N + 1
     }
 
-    #[test]
-    fn test_4() {
-        let mut ring = FrodoRing::<u8, 4>::new();
+    /// Находит ячейку для следующего отложенного в транзакции элемента.
+    fn stage_cell(&mut self, mut stage_head: usize) -> Option<usize> {
+        if self.limited_len() >= self.limit {
+            return None;
+        }
+
+        let span = if self.cap == 0 {
+            self.staged
+        } else {
+            self.stage_offset(stage_head) + self.staged
+        };
+
+        if span == N {
+            if self.is_pinned() || self.len() + self.staged == N {
+                return None;
+            }
+
+            // Ужимаем видимую часть и сдвигаем отложенные элементы вплотную к ней
+            self.compact();
+            let new_stage_head = self.real_pos(self.cap);
+            self.move_run(stage_head, new_stage_head, self.staged);
+            stage_head = new_stage_head;
+            self.staging = Some(stage_head);
+        }
+
+        Some((stage_head + self.staged) % N)
+    }
+
+    /// Кладёт элемент в очередь, а при её заполненности вытесняет элемент с наименьшим весом.
+    ///
+    /// Если вес нового элемента не больше наименьшего веса в очереди, элемент возвращается обратно.
+    /// Из нескольких элементов с наименьшим весом вытесняется самый старый. Возвращает вытесненный элемент.
+    pub fn push_weighted<W: Ord, F: Fn(&T) -> W>(
+        &mut self,
+        item: T,
+        weight: F,
+    ) -> Result<Option<T>, T> {
+        let item = match self.push_free(item) {
+            Ok(()) => return Ok(None),
+            Err(item) => item,
+        };
+
+        let result = self.evict_lighter(item, weight);
+        if result.is_err() {
+            self.count_rejection();
+        }
+        result
+    }
+
+    /// Вытесняет самый старый элемент с наименьшим весом, если новый элемент тяжелее, и кладёт новый.
+    fn evict_lighter<W: Ord, F: Fn(&T) -> W>(
+        &mut self,
+        item: T,
+        weight: F,
+    ) -> Result<Option<T>, T> {
+        let mut lightest: Option<(isize, W)> = None;
+        for naive_pos in 0..self.cap as isize {
+            if let Some(el) = self.at(naive_pos) {
+                let w = weight(el);
+                if lightest.as_ref().is_none_or(|(_, min)| w < *min) {
+                    lightest = Some((naive_pos, w));
+                }
+            }
+        }
+
+        match lightest {
+            Some((naive_pos, min)) if weight(&item) > min => {
+                // Вытесняем, только если освободившееся место гарантированно достанется новому элементу
+                let refill = self
+                    .checked_real_pos(naive_pos)
+                    .is_some_and(|real_pos| self.can_refill(real_pos));
+                if !refill {
+                    return Err(item);
+                }
+
+                let evicted = self.remove_at(naive_pos);
+                self.push_free(item)?;
+                Ok(evicted)
+            }
+            _ => Err(item),
+        }
+    }
+
+    /// Отдаёт первый элемент, изымая его из очереди.
+    ///
+    /// Если первая ячейка зарезервирована (`reserve()`), отдаёт первый из уже присутствующих элементов.
+    pub fn pick(&mut self) -> Option<T> {
+        let item = if self.cap > 0 && self.reserved[self.head] {
+            self.remove(0)
+        } else {
+            self.remove_at(0)
+        };
+
+        #[cfg(feature = "stats")]
+        if item.is_some() {
+            self.sample_depth();
+        }
+        item
+    }
+
+    /// Показывает первый элемент `f` и изымает его, только если `f` вернула `true`.
+    pub fn peek_then_remove<F: FnOnce(&T) -> bool>(&mut self, f: F) -> Option<T> {
+        let naive_pos = (0..self.cap).find(|naive_pos| self.occupied[self.real_pos(*naive_pos)])?;
+        self.peek_then_remove_at(naive_pos as isize, f)
+    }
+
+    /// Показывает последний (самый новый) элемент `f` и изымает его, только если `f` вернула `true`.
+    ///
+    /// Например, позволяет отменить последнюю поставленную команду, если её выполнение ещё не началось.
+    pub fn pop_back_if<F: FnOnce(&T) -> bool>(&mut self, f: F) -> Option<T> {
+        let naive_pos = (0..self.cap)
+            .rev()
+            .find(|naive_pos| self.occupied[self.real_pos(*naive_pos)])?;
+        self.peek_then_remove_at(naive_pos as isize, f)
+    }
+
+    /// Показывает `f` содержимое ячейки по наивной позиции и изымает его, только если `f` вернула `true`.
+    pub fn peek_then_remove_at<F: FnOnce(&T) -> bool>(
+        &mut self,
+        naive_pos: isize,
+        f: F,
+    ) -> Option<T> {
+        let real_pos = self.checked_real_pos(naive_pos)?;
+        if self.occupied[real_pos] && f(unsafe { self.buffer[real_pos].assume_init_ref() }) {
+            Some(self.take(real_pos))
+        } else {
+            None
+        }
+    }
+
+    /// Удаляет содержимое ячейки, находящейся по наивной позиции, и возвращает его.
+    pub fn remove_at(&mut self, naive_pos: isize) -> Option<T> {
+        if self.cap == 0 || naive_pos >= self.cap as isize || naive_pos < -(self.cap as isize) {
+            return None;
+        }
+
+        let real_pos = if naive_pos >= 0 {
+            self.real_pos(naive_pos as usize)
+        } else {
+            self.neg_pos((-naive_pos) as usize)
+        };
+
+        if self.occupied[real_pos] {
+            Some(self.take(real_pos))
+        } else {
+            None
+        }
+    }
+
+    /// Удаляет элемент по наивной позиции за `O(1)`, перенося на его место последний элемент очереди.
+    ///
+    /// Порядок оставшихся элементов нарушается, зато не остаётся пропуска и не требуется последующее сжатие.
+    /// Если элементы закреплены (`is_pinned()`), работает как `remove_at()`.
+    pub fn swap_remove_at(&mut self, naive_pos: isize) -> Option<T> {
+        let real_pos = self.checked_real_pos(naive_pos)?;
+        if !self.occupied[real_pos] {
+            return None;
+        }
+
+        let tail = self.real_pos(self.cap - 1);
+        if self.is_pinned() || tail == real_pos {
+            return Some(self.take(real_pos));
+        }
+
+        let item = unsafe { self.buffer[real_pos].assume_init_read() };
+        self.move_cell(tail, real_pos);
+        #[cfg(feature = "journal")]
+        let naive_pos = (real_pos + N - self.head) % N;
+        self.trim();
+        #[cfg(feature = "journal")]
+        self.record(journal::JournalOp::Take, naive_pos);
+        Some(item)
+    }
+
+    /// Изымает элемент из занятой ячейки по реальной позиции, поддерживая занятость краёв очереди.
+    fn take(&mut self, real_pos: usize) -> T {
+        self.occupied[real_pos] = false;
+        #[cfg(feature = "journal")]
+        let naive_pos = (real_pos + N - self.head) % N;
+        self.trim();
+        #[cfg(feature = "journal")]
+        self.record(journal::JournalOp::Take, naive_pos);
+        unsafe { self.buffer[real_pos].assume_init_read() }
+    }
+
+    /// Сдвигает начало и конец очереди так, чтобы крайние ячейки были заняты.
+    fn trim(&mut self) {
+        self.trim_edges();
+        self.check_watermarks();
+    }
+
+    /// Сдвигает начало и конец очереди, как `trim()`, но не проверяет пороги заполненности.
+    fn trim_edges(&mut self) {
+        while self.cap > 0 && !self.occupied[self.head] && !self.reserved[self.head] {
+            self.head = (self.head + 1) % N;
+            self.cap -= 1;
+        }
+        while self.cap > 0 && {
+            let tail = self.real_pos(self.cap - 1);
+            !self.occupied[tail] && !self.reserved[tail]
+        } {
+            self.cap -= 1;
+        }
+    }
+
+    /// Удаляет все элементы в диапазоне наивных позиций, возвращая число удалённых элементов.
+    ///
+    /// Пустые ячейки внутри диапазона пропускаются; позиции за пределами используемой ёмкости игнорируются.
+    pub fn remove_range<R: RangeBounds<usize>>(&mut self, naive_range: R) -> usize {
+        let start = match naive_range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match naive_range.end_bound() {
+            Bound::Included(end) => end.saturating_add(1),
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.cap,
+        }
+        .min(self.cap);
+
+        let mut removed = 0usize;
+        for naive_pos in start..end {
+            let real_pos = self.real_pos(naive_pos);
+            if self.occupied[real_pos] {
+                self.occupied[real_pos] = false;
+                unsafe { self.buffer[real_pos].assume_init_drop() };
+                removed += 1;
+            }
+        }
+
+        self.trim();
+        #[cfg(feature = "journal")]
+        if removed > 0 {
+            self.record(journal::JournalOp::TakeMany(removed), start);
+        }
+        removed
+    }
+
+    /// Сообщает, запрещено ли сейчас перемещать элементы в буфере (сжатие и сдвиги).
+    fn is_pinned(&self) -> bool {
+        self.reservations > 0 || self.pins > 0
+    }
+
+    /// Переносит содержимое ячейки `from` в ячейку `to` (по реальным позициям) вместе с признаком занятости.
+    fn move_cell(&mut self, from: usize, to: usize) {
+        self.occupied[to] = self.occupied[from];
+        if self.occupied[from] {
+            self.occupied[from] = false;
+            let item = unsafe { self.buffer[from].assume_init_read() };
+            self.buffer[to].write(item);
+        }
+    }
+
+    /// Переводит наивную позицию (в том числе отрицательную) в реальную, если она внутри используемой ёмкости.
+    fn checked_real_pos(&self, naive_pos: isize) -> Option<usize> {
+        if self.cap == 0 || naive_pos >= self.cap as isize || naive_pos < -(self.cap as isize) {
+            return None;
+        }
+
+        Some(if naive_pos >= 0 {
+            self.real_pos(naive_pos as usize)
+        } else {
+            self.neg_pos((-naive_pos) as usize)
+        })
+    }
+
+    /// Перемещает элемент из ячейки по наивной позиции в начало очереди, сохраняя порядок остальных элементов.
+    ///
+    /// Если перед началом очереди есть свободная ячейка, перемещение занимает `O(1)`; иначе элементы
+    /// до ближайшей пустой ячейки сдвигаются на одну позицию. Возвращает `false`, если ячейка пуста
+    /// или если сдвиг невозможен из-за незавершённых резервирований или закреплённых ячеек.
+    pub fn move_to_front(&mut self, naive_pos: isize) -> bool {
+        let Some(src) = self.checked_real_pos(naive_pos) else {
+            return false;
+        };
+        if !self.occupied[src] {
+            return false;
+        }
+        if src == self.head {
+            return true;
+        }
+
+        // Открытая транзакция может занять соседнюю ячейку следующим отложенным элементом
+        let before_head = (self.head + N - 1) % N;
+        let free_before_head = self.cap < N && self.staging.is_none();
+        if !free_before_head && self.is_pinned() {
+            return false;
+        }
+
+        let src_naive = (src + N - self.head) % N;
+        self.occupied[src] = false;
+        let item = unsafe { self.buffer[src].assume_init_read() };
+
+        if free_before_head {
+            self.head = before_head;
+            self.cap += 1;
+        } else {
+            let hole = (1..=src_naive)
+                .find(|n| !self.occupied[self.real_pos(*n)])
+                .unwrap_or(src_naive);
+            for n in (0..hole).rev() {
+                self.move_cell(self.real_pos(n), self.real_pos(n + 1));
+            }
+        }
+
+        self.buffer[self.head].write(item);
+        self.occupied[self.head] = true;
+        self.trim();
+        #[cfg(feature = "journal")]
+        self.record(journal::JournalOp::MoveToFront, src_naive);
+        true
+    }
+
+    /// Перемещает элемент из ячейки по наивной позиции в конец очереди, сохраняя порядок остальных элементов.
+    ///
+    /// Если после конца очереди есть свободная ячейка, перемещение занимает `O(1)`; иначе элементы
+    /// после ближайшей к концу пустой ячейки сдвигаются на одну позицию. Возвращает `false`, если ячейка пуста
+    /// или если сдвиг невозможен из-за незавершённых резервирований или закреплённых ячеек.
+    pub fn move_to_back(&mut self, naive_pos: isize) -> bool {
+        let Some(src) = self.checked_real_pos(naive_pos) else {
+            return false;
+        };
+        if !self.occupied[src] {
+            return false;
+        }
+        if src == self.real_pos(self.cap - 1) {
+            return true;
+        }
+
+        // Ячейка за концом очереди принадлежит открытой транзакции, даже если в ней ещё ничего не отложено
+        let after_tail = self.real_pos(self.cap);
+        let free_after_tail = self.cap < N && self.staging.is_none();
+        if !free_after_tail && self.is_pinned() {
+            return false;
+        }
+
+        let src_naive = (src + N - self.head) % N;
+        self.occupied[src] = false;
+        let item = unsafe { self.buffer[src].assume_init_read() };
+
+        let tail = if free_after_tail {
+            self.cap += 1;
+            after_tail
+        } else {
+            let hole = (src_naive..self.cap - 1)
+                .rev()
+                .find(|n| !self.occupied[self.real_pos(*n)])
+                .unwrap_or(src_naive);
+            for n in hole + 1..self.cap {
+                self.move_cell(self.real_pos(n), self.real_pos(n - 1));
+            }
+            self.real_pos(self.cap - 1)
+        };
+
+        self.buffer[tail].write(item);
+        self.occupied[tail] = true;
+        self.trim();
+        #[cfg(feature = "journal")]
+        self.record(journal::JournalOp::MoveToBack, src_naive);
+        true
+    }
+
+    /// Удаляет элемент из очереди.
+    pub fn remove(&mut self, pos: usize) -> Option<T> {
+        if pos >= self.cap || self.cap == 0 {
+            return None;
+        }
+
+        let mut cntr = 0usize;
+        let mut real_pos = self.head;
+        let max_cntr = self.len();
+
+        while cntr < max_cntr {
+            if self.occupied[real_pos] {
+                if cntr == pos {
+                    return Some(self.take(real_pos));
+                } else {
+                    cntr += 1;
+                }
+            }
+            real_pos = (real_pos + 1) % N;
+        }
+
+        None
+    }
+
+    /// Ужимает место в буфере, сохраняя порядок расположения элементов.
+    ///
+    /// Возвращает последнее пустое место (real_pos), куда можно вставить элемент.
+    ///
+    /// Важно: метод опирается на то, что первый элемент никогда не будет пустым (`self.real_pos(self.head)`).
+    fn compact(&mut self) -> Option<usize> {
+        assert!(self.cap > 0);
+        debug_assert!(!self.is_pinned());
+        self.compactions = self.compactions.wrapping_add(1);
+
+        let mut read_pos = 0usize;
+        let mut write_pos = 0usize;
+
+        while read_pos < self.cap {
+            // Пропуски просто перешагиваем
+            if !self.occupied[self.real_pos(read_pos)] {
+                read_pos += 1;
+                continue;
+            }
+
+            // Непрерывную серию занятых ячеек переносим целиком
+            let run_start = read_pos;
+            prefetch::prefetch(&self.buffer[self.real_pos(run_start)]);
+            while read_pos < self.cap && self.occupied[self.real_pos(read_pos)] {
+                read_pos += 1;
+            }
+            let run = read_pos - run_start;
+
+            if run_start != write_pos {
+                self.move_run(self.real_pos(run_start), self.real_pos(write_pos), run);
+            }
+            write_pos += run;
+        }
+
+        if write_pos == self.cap {
+            return None;
+        }
+
+        for naive_pos in 0..self.cap {
+            let real_pos = self.real_pos(naive_pos);
+            self.occupied[real_pos] = naive_pos < write_pos;
+        }
+        self.cap = write_pos;
+        #[cfg(feature = "journal")]
+        self.record(journal::JournalOp::Compact, 0);
+        Some(self.real_pos(self.cap))
+    }
+
+    /// Перемещает `len` подряд идущих ячеек с реальной позиции `from` на `to`, лежащую ближе к началу очереди.
+    ///
+    /// Флаги занятости не трогает. Копирует непрерывные участки буфера целиком, а не поэлементно.
+    fn move_run(&mut self, mut from: usize, mut to: usize, mut len: usize) {
+        let buffer = self.buffer.as_mut_ptr();
+        while len > 0 {
+            let chunk = len.min(N - from).min(N - to);
+            unsafe { core::ptr::copy(buffer.add(from), buffer.add(to), chunk) };
+            from = (from + chunk) % N;
+            to = (to + chunk) % N;
+            len -= chunk;
+        }
+    }
+}
+
+/// Ошибка `push_with()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushWithError<E> {
+    /// В очереди нет свободной ячейки.
+    Full,
+    /// Инициализация ячейки завершилась ошибкой.
+    Init(E),
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for PushWithError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Full => write!(f, "ring is full"),
+            Self::Init(err) => write!(f, "cell initialization failed: {err}"),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for PushWithError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Full => None,
+            Self::Init(err) => Some(err),
+        }
+    }
+}
+
+/// Итератор по элементам очереди.
+///
+/// При итерировании пропускает пустые ячейки, выдавая исключительно присутствующие элементы.
+pub struct FrodoRingIterator<'ring, T, const N: usize> {
+    ring: &'ring FrodoRing<T, N>,
+    naive_pos: usize,
+}
+
+impl<'ring, T, const N: usize> FrodoRingIterator<'ring, T, N> {
+    /// Проходит по оставшимся ячейкам непрерывными отрезками буфера, пока `f` не попросит остановиться.
+    ///
+    /// Позиция итератора сдвигается за последний просмотренный элемент, так что итерирование можно продолжить.
+    fn scan_cells<R>(&mut self, mut f: impl FnMut(&'ring T) -> ControlFlow<R>) -> Option<R> {
+        let ring = self.ring;
+        while self.naive_pos < ring.cap {
+            let start = ring.real_pos(self.naive_pos);
+            let end = (start + ring.cap - self.naive_pos).min(N);
+            for real_pos in start..end {
+                self.naive_pos += 1;
+                prefetch::prefetch(ring.buffer.as_ptr().wrapping_add(real_pos + 1));
+                if ring.occupied[real_pos] {
+                    if let ControlFlow::Break(res) =
+                        f(unsafe { ring.buffer[real_pos].assume_init_ref() })
+                    {
+                        return Some(res);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'ring, T, const N: usize> Iterator for FrodoRingIterator<'ring, T, N> {
+    type Item = &'ring T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.naive_pos == self.ring.cap {
+                return None;
+            }
+            let res = self.ring.at(self.naive_pos as isize);
+            self.naive_pos += 1;
+            if res.is_some() {
+                if self.naive_pos < self.ring.cap {
+                    prefetch::prefetch(&self.ring.buffer[self.ring.real_pos(self.naive_pos)]);
+                }
+                return res;
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.ring.cap - self.naive_pos))
+    }
+
+    /// Как `fold`, проходит по непрерывным отрезкам буфера, но останавливается на первом найденном элементе.
+    fn find<P: FnMut(&Self::Item) -> bool>(&mut self, mut predicate: P) -> Option<Self::Item> {
+        self.scan_cells(|item| match predicate(&item) {
+            true => ControlFlow::Break(item),
+            false => ControlFlow::Continue(()),
+        })
+    }
+
+    fn position<P: FnMut(Self::Item) -> bool>(&mut self, mut predicate: P) -> Option<usize> {
+        let mut index = 0;
+        self.scan_cells(|item| match predicate(item) {
+            true => ControlFlow::Break(index),
+            false => {
+                index += 1;
+                ControlFlow::Continue(())
+            }
+        })
+    }
+
+    fn any<F: FnMut(Self::Item) -> bool>(&mut self, mut f: F) -> bool {
+        self.scan_cells(|item| match f(item) {
+            true => ControlFlow::Break(()),
+            false => ControlFlow::Continue(()),
+        })
+        .is_some()
+    }
+
+    fn all<F: FnMut(Self::Item) -> bool>(&mut self, mut f: F) -> bool {
+        self.scan_cells(|item| match f(item) {
+            true => ControlFlow::Continue(()),
+            false => ControlFlow::Break(()),
+        })
+        .is_none()
+    }
+
+    /// Проходит по оставшимся ячейкам одним-двумя непрерывными отрезками буфера, без проверок `at()`.
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, mut f: F) -> B {
+        let ring = self.ring;
+        let len = ring.cap - self.naive_pos;
+        if len == 0 {
+            return init;
+        }
+
+        let start = ring.real_pos(self.naive_pos);
+        let first = len.min(N - start);
+        let second = len - first;
+
+        let cells = ring.occupied[start..start + first]
+            .iter()
+            .zip(&ring.buffer[start..start + first])
+            .chain(ring.occupied[..second].iter().zip(&ring.buffer[..second]));
+
+        cells.fold(init, |acc, (occupied, cell)| {
+            prefetch::prefetch((cell as *const MaybeUninit<T>).wrapping_add(1));
+            if *occupied {
+                f(acc, unsafe { cell.assume_init_ref() })
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_1() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert!(ring.push(0x5).is_err());
+    }
+
+    #[test]
+    fn test_2() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), Some(&0x2));
+        assert_eq!(ring.at(2), Some(&0x3));
+        assert_eq!(ring.at(3), Some(&0x4));
+        assert_eq!(ring.at(-1), Some(&0x4));
+        assert_eq!(ring.at(-2), Some(&0x3));
+        assert_eq!(ring.at(-3), Some(&0x2));
+        assert_eq!(ring.at(-4), Some(&0x1));
+
+        assert_eq!(ring.at(4), None);
+        assert_eq!(ring.at(-5), None);
+    }
+
+    #[test]
+    fn negative_positions_partial() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.at(-1), Some(&0x3));
+        assert_eq!(ring.at(-3), Some(&0x1));
+
+        assert_eq!(ring.pick(), Some(0x1));
+        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.at(-1), Some(&0x4));
+        assert_eq!(ring.at(-2), Some(&0x3));
+
+        assert_eq!(ring.remove_at(-1), Some(0x4));
+        assert_eq!(ring.at(-1), Some(&0x3));
+        assert_eq!(ring.len(), 2);
+    }
+
+    #[test]
+    fn test_3() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), Some(&0x3));
+        assert_eq!(ring.at(3), Some(&0x4));
+    }
+
+    #[test]
+    fn test_4() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), Some(&0x3));
+        assert_eq!(ring.at(3), Some(&0x4));
+
+        assert!(ring.push(0x5).is_ok());
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), Some(&0x3));
+        assert_eq!(ring.at(2), Some(&0x4));
+        assert_eq!(ring.at(3), Some(&0x5));
+    }
+
+    #[test]
+    fn massive() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert_eq!(ring.used(), 4);
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), Some(&0x3));
+        assert_eq!(ring.at(3), Some(&0x4));
+
+        assert!(ring.push(0x5).is_ok());
+        assert_eq!(ring.used(), 4);
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), Some(&0x3));
+        assert_eq!(ring.at(2), Some(&0x4));
+        assert_eq!(ring.at(3), Some(&0x5));
+
+        assert_eq!(ring.remove_at(0), Some(0x1));
+        assert_eq!(ring.used(), 3);
+        assert_eq!(ring.at(0), Some(&0x3));
+        assert_eq!(ring.at(1), Some(&0x4));
+        assert_eq!(ring.at(2), Some(&0x5));
+        assert_eq!(ring.at(3), None);
+
+        assert_eq!(ring.remove_at(1), Some(0x4));
+        assert_eq!(ring.used(), 3);
+        assert_eq!(ring.at(0), Some(&0x3));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), Some(&0x5));
+        assert_eq!(ring.at(3), None);
+
+        assert!(ring.push(0x6).is_ok());
+        assert_eq!(ring.used(), 4);
+        assert_eq!(ring.at(0), Some(&0x3));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), Some(&0x5));
+        assert_eq!(ring.at(3), Some(&0x6));
+
+        assert!(ring.push(0x7).is_ok());
+        assert_eq!(ring.used(), 4);
+        assert_eq!(ring.at(0), Some(&0x3));
+        assert_eq!(ring.at(1), Some(&0x5));
+        assert_eq!(ring.at(2), Some(&0x6));
+        assert_eq!(ring.at(3), Some(&0x7));
+
+        assert!(ring.push(0x8).is_err());
+    }
+
+    #[test]
+    fn iter() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        let mut it = ring.iter();
+        assert_eq!(it.next(), Some(&0x1));
+        assert_eq!(it.next(), Some(&0x3));
+        assert_eq!(it.next(), Some(&0x4));
+        assert_eq!(it.next(), None);
+
+        assert!(ring.push(0x5).is_ok());
+        let mut it = ring.iter();
+        assert_eq!(it.next(), Some(&0x1));
+        assert_eq!(it.next(), Some(&0x3));
+        assert_eq!(it.next(), Some(&0x4));
+        assert_eq!(it.next(), Some(&0x5));
+        assert_eq!(it.next(), None);
+
+        assert_eq!(ring.remove_at(0), Some(0x1));
+        let mut it = ring.iter();
+        assert_eq!(it.next(), Some(&0x3));
+        assert_eq!(it.next(), Some(&0x4));
+        assert_eq!(it.next(), Some(&0x5));
+        assert_eq!(it.next(), None);
+
+        assert_eq!(ring.remove_at(1), Some(0x4));
+        let mut it = ring.iter();
+        assert_eq!(it.next(), Some(&0x3));
+        assert_eq!(it.next(), Some(&0x5));
+        assert_eq!(ring.at(3), None);
+
+        assert!(ring.push(0x6).is_ok());
+        let mut it = ring.iter();
+        assert_eq!(it.next(), Some(&0x3));
+        assert_eq!(it.next(), Some(&0x5));
+        assert_eq!(it.next(), Some(&0x6));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+
+        assert!(ring.push(0x7).is_ok());
+        let mut it = ring.iter();
+        assert_eq!(it.next(), Some(&0x3));
+        assert_eq!(it.next(), Some(&0x5));
+        assert_eq!(it.next(), Some(&0x6));
+        assert_eq!(it.next(), Some(&0x7));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_5() {
+        let mut ring = FrodoRing::<u8, 4>::new();
 
         assert!(ring.push(0x1).is_ok());
         assert!(ring.push(0x2).is_ok());
@@ -420,20 +1613,29 @@ mod tests {
         assert!(ring.push(0x4).is_ok());
 
         assert_eq!(ring.remove_at(1), Some(0x2));
+        assert_eq!(ring.used(), 4);
         assert_eq!(ring.at(0), Some(&0x1));
         assert_eq!(ring.at(1), None);
         assert_eq!(ring.at(2), Some(&0x3));
         assert_eq!(ring.at(3), Some(&0x4));
 
-        assert!(ring.push(0x5).is_ok());
+        assert_eq!(ring.remove_at(2), Some(0x3));
+        assert_eq!(ring.used(), 4);
         assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), Some(&0x3));
-        assert_eq!(ring.at(2), Some(&0x4));
-        assert_eq!(ring.at(3), Some(&0x5));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), None);
+        assert_eq!(ring.at(3), Some(&0x4));
+
+        assert_eq!(ring.remove_at(0), Some(0x1));
+        assert_eq!(ring.used(), 1);
+        assert_eq!(ring.at(0), Some(&0x4));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), None);
+        assert_eq!(ring.at(3), None);
     }
 
     #[test]
-    fn massive() {
+    fn test_6() {
         let mut ring = FrodoRing::<u8, 4>::new();
 
         assert!(ring.push(0x1).is_ok());
@@ -448,237 +1650,657 @@ mod tests {
         assert_eq!(ring.at(2), Some(&0x3));
         assert_eq!(ring.at(3), Some(&0x4));
 
-        assert!(ring.push(0x5).is_ok());
+        assert_eq!(ring.remove_at(2), Some(0x3));
         assert_eq!(ring.used(), 4);
         assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), Some(&0x3));
-        assert_eq!(ring.at(2), Some(&0x4));
-        assert_eq!(ring.at(3), Some(&0x5));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), None);
+        assert_eq!(ring.at(3), Some(&0x4));
 
-        assert_eq!(ring.remove_at(0), Some(0x1));
-        assert_eq!(ring.used(), 3);
-        assert_eq!(ring.at(0), Some(&0x3));
-        assert_eq!(ring.at(1), Some(&0x4));
-        assert_eq!(ring.at(2), Some(&0x5));
+        assert_eq!(ring.remove_at(3), Some(0x4));
+        assert_eq!(ring.used(), 1);
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), None);
         assert_eq!(ring.at(3), None);
+    }
 
-        assert_eq!(ring.remove_at(1), Some(0x4));
-        assert_eq!(ring.used(), 3);
-        assert_eq!(ring.at(0), Some(&0x3));
+    #[test]
+    fn test_7() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert_eq!(ring.pick(), Some(0x1));
+        assert_eq!(ring.pick(), Some(0x2));
+        assert_eq!(ring.pick(), Some(0x3));
+        assert_eq!(ring.pick(), Some(0x4));
+        assert_eq!(ring.pick(), None);
+    }
+
+    #[test]
+    fn test_8() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), Some(&0x2));
+        assert_eq!(ring.at(2), Some(&0x3));
+        assert_eq!(ring.at(3), Some(&0x4));
+        assert_eq!(ring.get(0), Some(&0x1));
+        assert_eq!(ring.get(1), Some(&0x2));
+        assert_eq!(ring.get(2), Some(&0x3));
+        assert_eq!(ring.get(3), Some(&0x4));
+
+        assert_eq!(ring.get(4), None);
+
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert_eq!(ring.used(), 4);
+        assert_eq!(ring.at(0), Some(&0x1));
         assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), Some(&0x5));
+        assert_eq!(ring.at(2), Some(&0x3));
+        assert_eq!(ring.at(3), Some(&0x4));
+        assert_eq!(ring.get(0), Some(&0x1));
+        assert_eq!(ring.get(1), Some(&0x3));
+        assert_eq!(ring.get(2), Some(&0x4));
+        assert_eq!(ring.get(3), None);
+    }
+
+    #[test]
+    fn test_9() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+
+        assert_eq!(ring.remove(1), Some(0x2));
+        assert_eq!(ring.used(), 4);
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), Some(&0x3));
+        assert_eq!(ring.at(3), Some(&0x4));
+
+        assert_eq!(ring.remove(1), Some(0x3));
+        assert_eq!(ring.used(), 4);
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), None);
+        assert_eq!(ring.at(3), Some(&0x4));
+
+        assert_eq!(ring.remove(1), Some(0x4));
+        assert_eq!(ring.used(), 1);
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), None);
         assert_eq!(ring.at(3), None);
+    }
+
+    #[test]
+    fn transaction() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+
+        ring.begin();
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.staged(), 2);
+        assert_eq!(ring.len(), 1);
+        assert_eq!(ring.at(1), None);
+
+        assert_eq!(ring.pick(), Some(0x1));
+        assert_eq!(ring.pick(), None);
+
+        ring.commit();
+        assert_eq!(ring.staged(), 0);
+        assert_eq!(ring.pick(), Some(0x2));
+        assert_eq!(ring.pick(), Some(0x3));
+        assert_eq!(ring.pick(), None);
+
+        ring.begin();
+        assert!(ring.push(0x4).is_ok());
+        ring.rollback();
+        assert!(ring.is_empty());
+        assert!(ring.push(0x5).is_ok());
+        assert_eq!(ring.pick(), Some(0x5));
+    }
+
+    #[test]
+    fn transaction_compact() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+
+        ring.begin();
+        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert_eq!(ring.remove_at(2), Some(0x3));
+        assert!(ring.push(0x5).is_ok());
+        assert!(ring.push(0x6).is_ok());
+        assert_eq!(ring.push(0x7), Err(0x7));
+        assert_eq!(ring.len(), 1);
+
+        ring.commit();
+        assert_eq!(ring.len(), 4);
+        assert_eq!(ring.pick(), Some(0x1));
+        assert_eq!(ring.pick(), Some(0x4));
+        assert_eq!(ring.pick(), Some(0x5));
+        assert_eq!(ring.pick(), Some(0x6));
+        assert_eq!(ring.pick(), None);
+    }
+
+    #[test]
+    fn remove_range() {
+        let mut ring = FrodoRing::<u8, 6>::new();
+
+        for i in 0x1..=0x6 {
+            assert!(ring.push(i).is_ok());
+        }
+
+        assert_eq!(ring.remove_at(2), Some(0x3));
+        assert_eq!(ring.remove_range(1..4), 2);
+        assert_eq!(ring.used(), 6);
+        assert_eq!(ring.at(0), Some(&0x1));
+        assert_eq!(ring.at(4), Some(&0x5));
+
+        assert_eq!(ring.remove_range(4..), 2);
+        assert_eq!(ring.used(), 1);
+        assert_eq!(ring.remove_range(..=0), 1);
+        assert!(ring.is_empty());
+        assert_eq!(ring.remove_range(..), 0);
+    }
+
+    #[test]
+    fn iter_indexed() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x2));
 
-        assert!(ring.push(0x6).is_ok());
-        assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x3));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), Some(&0x5));
-        assert_eq!(ring.at(3), Some(&0x6));
+        let mut it = ring.iter_indexed();
+        assert_eq!(it.next(), Some((0, &0x1)));
+        assert_eq!(it.next(), Some((1, &0x3)));
+        assert_eq!(it.next(), None);
+    }
 
-        assert!(ring.push(0x7).is_ok());
-        assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x3));
-        assert_eq!(ring.at(1), Some(&0x5));
-        assert_eq!(ring.at(2), Some(&0x6));
-        assert_eq!(ring.at(3), Some(&0x7));
+    #[test]
+    fn peek_n() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        assert_eq!(ring.peek_n::<2>(), [None, None]);
 
-        assert!(ring.push(0x8).is_err());
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x2));
+
+        assert_eq!(ring.peek_n::<1>(), [Some(&0x1)]);
+        assert_eq!(ring.peek_n::<3>(), [Some(&0x1), Some(&0x3), None]);
     }
 
     #[test]
-    fn iter() {
+    fn last_n() {
         let mut ring = FrodoRing::<u8, 4>::new();
+        assert_eq!(ring.last_n::<2>(), [None, None]);
 
         assert!(ring.push(0x1).is_ok());
         assert!(ring.push(0x2).is_ok());
         assert!(ring.push(0x3).is_ok());
-        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x2));
+
+        assert_eq!(ring.last_n::<1>(), [Some(&0x3)]);
+        assert_eq!(ring.last_n::<3>(), [None, Some(&0x1), Some(&0x3)]);
+    }
+
+    #[test]
+    fn map_in_place() {
+        let mut ring = FrodoRing::<u8, 4>::new();
 
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
         assert_eq!(ring.remove_at(1), Some(0x2));
-        let mut it = ring.iter();
-        assert_eq!(it.next(), Some(&0x1));
-        assert_eq!(it.next(), Some(&0x3));
-        assert_eq!(it.next(), Some(&0x4));
-        assert_eq!(it.next(), None);
 
+        let mut order = 0u8;
+        ring.map_in_place(|el| {
+            order += 1;
+            *el = *el * 0x10 + order;
+        });
+        assert_eq!(ring.at(0), Some(&0x11));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.at(2), Some(&0x32));
+    }
+
+    #[test]
+    fn iter_fold() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        assert_eq!(ring.iter().sum::<u8>(), 0);
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.pick(), Some(0x1));
+        assert_eq!(ring.pick(), Some(0x2));
         assert!(ring.push(0x5).is_ok());
-        let mut it = ring.iter();
-        assert_eq!(it.next(), Some(&0x1));
-        assert_eq!(it.next(), Some(&0x3));
-        assert_eq!(it.next(), Some(&0x4));
-        assert_eq!(it.next(), Some(&0x5));
-        assert_eq!(it.next(), None);
+        assert!(ring.push(0x6).is_ok());
+        assert_eq!(ring.remove_at(2), Some(0x5));
 
-        assert_eq!(ring.remove_at(0), Some(0x1));
-        let mut it = ring.iter();
-        assert_eq!(it.next(), Some(&0x3));
-        assert_eq!(it.next(), Some(&0x4));
-        assert_eq!(it.next(), Some(&0x5));
-        assert_eq!(it.next(), None);
+        assert_eq!(ring.iter().sum::<u8>(), 0x3 + 0x4 + 0x6);
+        assert_eq!(ring.iter().count(), 3);
 
-        assert_eq!(ring.remove_at(1), Some(0x4));
         let mut it = ring.iter();
         assert_eq!(it.next(), Some(&0x3));
-        assert_eq!(it.next(), Some(&0x5));
-        assert_eq!(ring.at(3), None);
+        assert_eq!(it.fold(0, |acc, el| acc * 0x10 + *el), 0x46);
+    }
 
+    #[test]
+    fn iter_short_circuit() {
+        let mut ring = FrodoRing::<u8, 5>::new();
+        for i in 0x1..=0x5 {
+            assert!(ring.push(i).is_ok());
+        }
+        assert_eq!(ring.pick(), Some(0x1));
+        assert_eq!(ring.remove_at(1), Some(0x3));
         assert!(ring.push(0x6).is_ok());
+
+        let mut calls = 0;
+        assert!(ring.iter().any(|el| {
+            calls += 1;
+            *el == 0x4
+        }));
+        assert_eq!(calls, 2);
+
         let mut it = ring.iter();
-        assert_eq!(it.next(), Some(&0x3));
-        assert_eq!(it.next(), Some(&0x5));
+        assert_eq!(it.find(|el| **el > 0x4), Some(&0x5));
         assert_eq!(it.next(), Some(&0x6));
         assert_eq!(it.next(), None);
-        assert_eq!(it.next(), None);
-        assert_eq!(it.next(), None);
 
-        assert!(ring.push(0x7).is_ok());
         let mut it = ring.iter();
-        assert_eq!(it.next(), Some(&0x3));
-        assert_eq!(it.next(), Some(&0x5));
-        assert_eq!(it.next(), Some(&0x6));
-        assert_eq!(it.next(), Some(&0x7));
-        assert_eq!(it.next(), None);
+        assert_eq!(it.position(|el| *el == 0x5), Some(2));
+        assert_eq!(it.position(|el| *el == 0x6), Some(0));
+        assert_eq!(it.position(|_| true), None);
+
+        assert!(ring.iter().all(|el| *el > 0x1));
+        assert!(!ring.iter().all(|el| *el < 0x6));
+        assert_eq!(ring.iter().find(|el| **el == 0x3), None);
     }
 
     #[test]
-    fn test_5() {
+    fn queries() {
+        struct Frame(u8);
+
+        let mut ring = FrodoRing::<Frame, 4>::new();
+        assert!(ring.all(|f| f.0 > 0x10));
+        assert!(!ring.any(|f| f.0 > 0x10));
+
+        assert!(ring.push(Frame(0x1)).is_ok());
+        assert!(ring.push(Frame(0x2)).is_ok());
+        assert!(ring.push(Frame(0x3)).is_ok());
+        assert!(ring.remove_at(1).is_some());
+
+        assert_eq!(ring.count_matching(|f| f.0 % 2 == 1), 2);
+        assert!(ring.any(|f| f.0 == 0x3));
+        assert!(!ring.any(|f| f.0 == 0x2));
+        assert!(ring.all(|f| f.0 < 0x4));
+        assert_eq!(ring.iter().map(|f| f.0).max(), Some(0x3));
+    }
+
+    #[test]
+    fn position() {
         let mut ring = FrodoRing::<u8, 4>::new();
 
         assert!(ring.push(0x1).is_ok());
         assert!(ring.push(0x2).is_ok());
         assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.pick(), Some(0x1));
         assert!(ring.push(0x4).is_ok());
+        assert!(ring.push(0x5).is_ok());
 
-        assert_eq!(ring.remove_at(1), Some(0x2));
-        assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), Some(&0x3));
-        assert_eq!(ring.at(3), Some(&0x4));
+        let pos = ring.position(|el| *el == 0x5).unwrap();
+        assert_eq!(pos, 3);
+        assert_eq!(ring.at(pos), Some(&0x5));
+        assert_eq!(ring.position(|el| *el == 0x1), None);
+    }
 
-        assert_eq!(ring.remove_at(2), Some(0x3));
+    #[test]
+    fn move_to_front() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+
+        assert!(ring.move_to_front(2));
+        assert_eq!(ring.used(), 3);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0x3, 0x1, 0x2]);
+
+        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x1));
+        assert!(ring.move_to_front(2));
         assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0x2, 0x3, 0x4]);
         assert_eq!(ring.at(2), None);
-        assert_eq!(ring.at(3), Some(&0x4));
 
-        assert_eq!(ring.remove_at(0), Some(0x1));
-        assert_eq!(ring.used(), 1);
-        assert_eq!(ring.at(0), Some(&0x4));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), None);
-        assert_eq!(ring.at(3), None);
+        assert!(ring.move_to_front(-1));
+        assert_eq!(ring.used(), 3);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0x4, 0x2, 0x3]);
+
+        assert!(ring.move_to_front(0));
+        assert!(!ring.move_to_front(3));
     }
 
     #[test]
-    fn test_6() {
-        let mut ring = FrodoRing::<u8, 4>::new();
+    fn move_in_transaction() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        ring.begin();
+        assert!(ring.move_to_back(0));
+        assert!(ring.push(0x4).is_ok());
+        ring.commit();
+        assert!(ring.iter().eq([&0x2, &0x3, &0x1, &0x4]));
 
+        let mut ring = FrodoRing::<u8, 4>::new();
         assert!(ring.push(0x1).is_ok());
         assert!(ring.push(0x2).is_ok());
         assert!(ring.push(0x3).is_ok());
+        ring.begin();
+        assert!(ring.move_to_front(2));
         assert!(ring.push(0x4).is_ok());
+        ring.commit();
+        assert!(ring.iter().eq([&0x3, &0x1, &0x2, &0x4]));
+    }
 
-        assert_eq!(ring.remove_at(1), Some(0x2));
-        assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), Some(&0x3));
-        assert_eq!(ring.at(3), Some(&0x4));
+    #[test]
+    fn move_to_back() {
+        let mut ring = FrodoRing::<u8, 4>::new();
 
-        assert_eq!(ring.remove_at(2), Some(0x3));
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+
+        assert!(ring.move_to_back(0));
+        assert_eq!(ring.used(), 3);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0x2, 0x3, 0x1]);
+
+        assert!(ring.push(0x4).is_ok());
+        assert!(ring.move_to_back(1));
         assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
+        assert_eq!(
+            ring.iter().copied().collect::<Vec<_>>(),
+            [0x2, 0x1, 0x4, 0x3]
+        );
+
+        assert_eq!(ring.remove_at(1), Some(0x1));
+        assert!(ring.move_to_back(0));
+        assert_eq!(ring.used(), 3);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0x4, 0x3, 0x2]);
+    }
+
+    #[test]
+    fn select_nth() {
+        let mut ring = FrodoRing::<u8, 6>::new();
+
+        for el in [0x5, 0x1, 0x4, 0x2, 0x3, 0x6] {
+            assert!(ring.push(el).is_ok());
+        }
+        assert_eq!(ring.remove_at(-1), Some(0x6));
+
+        assert_eq!(ring.select_nth_unstable_by(0, |a, b| a.cmp(b)), Some(&0x1));
+        assert_eq!(ring.select_nth_unstable_by(2, |a, b| a.cmp(b)), Some(&0x3));
+        assert_eq!(ring.select_nth_unstable_by(0, |a, b| b.cmp(a)), Some(&0x5));
+        assert_eq!(ring.select_nth_unstable_by(5, |a, b| a.cmp(b)), None);
+
+        assert_eq!(
+            ring.iter().copied().collect::<Vec<_>>(),
+            [0x5, 0x1, 0x4, 0x2, 0x3]
+        );
+
+        assert!(ring.push(0x2).is_ok());
+        assert_eq!(ring.select_nth_unstable_by(1, |a, b| a.cmp(b)), Some(&0x2));
+        assert_eq!(ring.select_nth_unstable_by(2, |a, b| a.cmp(b)), Some(&0x2));
+        assert_eq!(ring.select_nth_unstable_by(3, |a, b| a.cmp(b)), Some(&0x3));
+    }
+
+    #[test]
+    fn partition_in_place() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+        for i in 1..=7 {
+            ring.push(i).unwrap();
+        }
+        ring.remove_at(2);
+
+        assert_eq!(ring.partition_in_place(|el| el % 2 == 0), Some(3));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [2, 4, 6, 1, 5, 7]);
         assert_eq!(ring.at(2), None);
-        assert_eq!(ring.at(3), Some(&0x4));
 
-        assert_eq!(ring.remove_at(3), Some(0x4));
-        assert_eq!(ring.used(), 1);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.partition_in_place(|el| *el == 7), Some(1));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [7, 2, 4, 6, 1, 5]);
+        assert_eq!(ring.partition_in_place(|_| false), Some(0));
+        assert_eq!(ring.partition_in_place(|el| *el < 5), Some(3));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [2, 4, 1, 7, 6, 5]);
         assert_eq!(ring.at(2), None);
-        assert_eq!(ring.at(3), None);
+
+        let slot = ring.reserve().unwrap();
+        assert_eq!(ring.partition_in_place(|el| *el > 4), None);
+        ring.abort_slot(slot);
     }
 
     #[test]
-    fn test_7() {
+    fn layout() {
+        assert_eq!(
+            FrodoRing::<u32, 8>::alignment(),
+            core::mem::align_of::<usize>()
+        );
+        assert!(FrodoRing::<u32, 8>::footprint() >= 8 * 4 + 8 + 8);
+
+        const FOOTPRINT: usize = FrodoRing::<u8, 16>::footprint();
+        assert_eq!(FOOTPRINT, core::mem::size_of::<FrodoRing<u8, 16>>());
+    }
+
+    #[test]
+    fn debug_compact() {
+        let mut ring = FrodoRing::<u8, 6>::new();
+        for i in 1..=4 {
+            ring.push(i).unwrap();
+        }
+        ring.remove_at(1);
+        ring.pick();
+        let slot = ring.reserve().unwrap();
+
+        assert_eq!(format!("{ring:#?}"), "head=2 cap=3 len=2 [..##r.] [3, 4]");
+        ring.abort_slot(slot);
+
+        let mut ring = FrodoRing::<u8, 20>::new();
+        ring.push(1).unwrap();
+        assert_eq!(
+            format!("{ring:#?}"),
+            "head=0 cap=1 len=1 [#...................]"
+        );
+    }
+
+    #[test]
+    fn from_fn_and_fill() {
+        let ring = FrodoRing::<usize, 4>::from_fn(|i| i * 10);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0, 10, 20, 30]);
+        assert_eq!(ring.used(), 4);
+
+        let mut ring = FrodoRing::<u8, 3>::fill(7);
+        assert_eq!(ring.len(), 3);
+        assert!(ring.push(8).is_err());
+        assert_eq!(ring.pick(), Some(7));
+    }
+
+    #[test]
+    fn naive_real_mapping() {
         let mut ring = FrodoRing::<u8, 4>::new();
+        for i in 0..4 {
+            assert!(ring.push(i).is_ok());
+        }
+        assert_eq!(ring.pick(), Some(0));
+        assert_eq!(ring.pick(), Some(1));
+        assert!(ring.push(4).is_ok());
+
+        assert_eq!(ring.head(), 2);
+        assert_eq!(ring.naive_to_real(2), Some(0));
+        assert_eq!(ring.naive_to_real(-1), Some(0));
+        assert_eq!(ring.naive_to_real(3), None);
+        assert_eq!(ring.real_to_naive(0), Some(2));
+        assert_eq!(ring.real_to_naive(1), None);
+        assert_eq!(ring.real_to_naive(4), None);
+    }
 
+    #[test]
+    fn pop_back_if() {
+        let mut ring = FrodoRing::<(u8, bool), 4>::new();
+        assert!(ring.push((0x1, false)).is_ok());
+        assert!(ring.push((0x2, true)).is_ok());
+
+        assert_eq!(ring.pop_back_if(|(_, started)| !started), None);
+        assert!(ring.push((0x3, false)).is_ok());
+        assert_eq!(
+            ring.pop_back_if(|(_, started)| !started),
+            Some((0x3, false))
+        );
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.used(), 2);
+    }
+
+    #[test]
+    fn is_full() {
+        let mut ring = FrodoRing::<u8, 3>::new();
         assert!(ring.push(0x1).is_ok());
         assert!(ring.push(0x2).is_ok());
+        assert!(!ring.is_full());
         assert!(ring.push(0x3).is_ok());
+        assert!(ring.is_full());
+
+        // Пропуск будет ужат при следующем push()
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert!(!ring.is_full());
+        assert_eq!(ring.contiguous_free_space(), 0);
         assert!(ring.push(0x4).is_ok());
+        assert!(ring.is_full());
 
         assert_eq!(ring.pick(), Some(0x1));
-        assert_eq!(ring.pick(), Some(0x2));
-        assert_eq!(ring.pick(), Some(0x3));
-        assert_eq!(ring.pick(), Some(0x4));
-        assert_eq!(ring.pick(), None);
+        assert_eq!(ring.contiguous_free_space(), 1);
+        ring.begin();
+        assert!(ring.push(0x5).is_ok());
+        assert!(ring.is_full());
+        assert_eq!(ring.contiguous_free_space(), 0);
     }
 
     #[test]
-    fn test_8() {
+    fn swap_remove_at() {
         let mut ring = FrodoRing::<u8, 4>::new();
-
         assert!(ring.push(0x1).is_ok());
         assert!(ring.push(0x2).is_ok());
         assert!(ring.push(0x3).is_ok());
         assert!(ring.push(0x4).is_ok());
 
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), Some(&0x2));
-        assert_eq!(ring.at(2), Some(&0x3));
-        assert_eq!(ring.at(3), Some(&0x4));
-        assert_eq!(ring.get(0), Some(&0x1));
-        assert_eq!(ring.get(1), Some(&0x2));
-        assert_eq!(ring.get(2), Some(&0x3));
-        assert_eq!(ring.get(3), Some(&0x4));
+        assert_eq!(ring.swap_remove_at(1), Some(0x2));
+        assert_eq!(ring.used(), 3);
+        assert!(ring.iter().eq([&0x1, &0x4, &0x3]));
+        assert_eq!(ring.swap_remove_at(-1), Some(0x3));
+        assert_eq!(ring.swap_remove_at(5), None);
+        assert!(ring.push(0x5).is_ok());
+        assert!(ring.iter().eq([&0x1, &0x4, &0x5]));
+    }
 
-        assert_eq!(ring.get(4), None);
+    #[test]
+    fn push_weighted_pinned() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        assert!(ring.push(0x5).is_ok());
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x7).is_ok());
+        let slot = ring.reserve().unwrap();
 
-        assert_eq!(ring.remove_at(1), Some(0x2));
-        assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), Some(&0x3));
-        assert_eq!(ring.at(3), Some(&0x4));
-        assert_eq!(ring.get(0), Some(&0x1));
-        assert_eq!(ring.get(1), Some(&0x3));
-        assert_eq!(ring.get(2), Some(&0x4));
-        assert_eq!(ring.get(3), None);
+        // Пропуск внутри закреплённой очереди не ужать: вытеснение не должно терять элемент
+        assert_eq!(ring.push_weighted(0x9, |el| *el), Err(0x9));
+        assert!(ring.iter().eq([&0x5, &0x1, &0x7]));
+
+        ring.abort_slot(slot);
+        assert_eq!(ring.push_weighted(0x9, |el| *el), Ok(None));
+        assert_eq!(ring.push_weighted(0x8, |el| *el), Ok(Some(0x1)));
+        assert!(ring.iter().eq([&0x5, &0x7, &0x9, &0x8]));
     }
 
     #[test]
-    fn test_9() {
+    fn push_weighted() {
+        let mut ring = FrodoRing::<u8, 3>::new();
+
+        assert_eq!(ring.push_weighted(0x12, |el| *el & 0xF), Ok(None));
+        assert_eq!(ring.push_weighted(0x21, |el| *el & 0xF), Ok(None));
+        assert_eq!(ring.push_weighted(0x33, |el| *el & 0xF), Ok(None));
+
+        assert_eq!(ring.push_weighted(0x41, |el| *el & 0xF), Err(0x41));
+        assert_eq!(ring.push_weighted(0x52, |el| *el & 0xF), Ok(Some(0x21)));
+        assert_eq!(ring.push_weighted(0x63, |el| *el & 0xF), Ok(Some(0x12)));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0x33, 0x52, 0x63]);
+    }
+
+    #[test]
+    fn push_with() {
+        let mut ring = FrodoRing::<[u8; 64], 2>::new();
+
+        let res = unsafe {
+            ring.push_with(|slot| {
+                slot.write([0x1; 64]);
+                Ok::<_, ()>(())
+            })
+        };
+        assert_eq!(res, Ok(()));
+
+        let res = unsafe { ring.push_with(|_| Err(0xE)) };
+        assert_eq!(res, Err(PushWithError::Init(0xE)));
+        assert_eq!(ring.len(), 1);
+
+        let res = unsafe {
+            ring.push_with(|slot| {
+                let frame = slot.write([0; 64]);
+                frame[63] = 0x2;
+                Ok::<_, ()>(())
+            })
+        };
+        assert_eq!(res, Ok(()));
+        assert_eq!(
+            unsafe { ring.push_with(|_| Ok::<_, ()>(())) },
+            Err(PushWithError::Full)
+        );
+
+        assert_eq!(ring.pick(), Some([0x1; 64]));
+        assert_eq!(ring.pick().map(|frame| frame[63]), Some(0x2));
+    }
+
+    #[test]
+    fn peek_then_remove() {
         let mut ring = FrodoRing::<u8, 4>::new();
+        assert_eq!(ring.peek_then_remove(|_| true), None);
 
         assert!(ring.push(0x1).is_ok());
         assert!(ring.push(0x2).is_ok());
         assert!(ring.push(0x3).is_ok());
-        assert!(ring.push(0x4).is_ok());
-
-        assert_eq!(ring.remove(1), Some(0x2));
-        assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), Some(&0x3));
-        assert_eq!(ring.at(3), Some(&0x4));
 
-        assert_eq!(ring.remove(1), Some(0x3));
-        assert_eq!(ring.used(), 4);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), None);
-        assert_eq!(ring.at(3), Some(&0x4));
+        assert_eq!(ring.peek_then_remove(|el| *el == 0x2), None);
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.peek_then_remove(|el| *el == 0x1), Some(0x1));
 
-        assert_eq!(ring.remove(1), Some(0x4));
-        assert_eq!(ring.used(), 1);
-        assert_eq!(ring.at(0), Some(&0x1));
-        assert_eq!(ring.at(1), None);
-        assert_eq!(ring.at(2), None);
-        assert_eq!(ring.at(3), None);
+        assert_eq!(ring.peek_then_remove_at(-1, |el| *el > 0x3), None);
+        assert_eq!(ring.peek_then_remove_at(-1, |el| *el == 0x3), Some(0x3));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0x2]);
     }
 }
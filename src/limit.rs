@@ -0,0 +1,125 @@
+//! Логическое ограничение ёмкости очереди во время работы.
+
+use crate::FrodoRing;
+
+/// Ошибка установки логического ограничения ёмкости.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitError {
+    /// Ограничение превышает размер буфера `N`.
+    AboveCapacity { capacity: usize },
+    /// В очереди уже больше элементов, чем позволяет ограничение.
+    TooManyElements { len: usize },
+}
+
+impl core::fmt::Display for LimitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::AboveCapacity { capacity } => write!(f, "limit exceeds ring capacity {capacity}"),
+            Self::TooManyElements { len } => {
+                write!(f, "ring already holds {len} elements, more than the limit")
+            }
+        }
+    }
+}
+
+impl core::error::Error for LimitError {}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает логическое ограничение числа элементов в очереди.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Ограничивает число элементов в очереди значением `limit`, не превышающим `N`.
+    ///
+    /// Отложенные в открытой транзакции элементы учитываются наравне с видимыми.
+    pub fn set_limit(&mut self, limit: usize) -> Result<(), LimitError> {
+        if limit > N {
+            return Err(LimitError::AboveCapacity { capacity: N });
+        }
+
+        let len = self.limited_len();
+        if len > limit {
+            return Err(LimitError::TooManyElements { len });
+        }
+
+        self.limit = limit;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_limit() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        assert_eq!(ring.limit(), 4);
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+
+        assert_eq!(
+            ring.set_limit(5),
+            Err(LimitError::AboveCapacity { capacity: 4 })
+        );
+        assert_eq!(
+            ring.set_limit(2),
+            Err(LimitError::TooManyElements { len: 3 })
+        );
+        assert_eq!(ring.set_limit(3), Ok(()));
+        assert_eq!(ring.push(0x4), Err(0x4));
+
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.push(0x5), Err(0x5));
+
+        ring.begin();
+        assert_eq!(ring.pick(), Some(0x1));
+        assert!(ring.push(0x5).is_ok());
+        assert_eq!(ring.push(0x6), Err(0x6));
+        ring.commit();
+
+        assert_eq!(ring.set_limit(4), Ok(()));
+        assert!(ring.push(0x6).is_ok());
+        assert_eq!(
+            ring.iter().copied().collect::<Vec<_>>(),
+            [0x3, 0x4, 0x5, 0x6]
+        );
+    }
+
+    #[test]
+    fn limit_counts_reservations() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+        assert!(ring.push(0x1).is_ok());
+        let slot = ring.reserve().unwrap();
+
+        assert_eq!(
+            ring.set_limit(1),
+            Err(LimitError::TooManyElements { len: 2 })
+        );
+        assert_eq!(ring.set_limit(2), Ok(()));
+        assert!(ring.is_full());
+        assert_eq!(ring.reserve(), None);
+        assert_eq!(ring.push(0x3), Err(0x3));
+        assert_eq!(ring.push_slice(&[0x3]), 0);
+
+        ring.abort_slot(slot);
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.push(0x4), Err(0x4));
+    }
+
+    #[test]
+    fn limit_error_propagates() {
+        fn shrink(ring: &mut FrodoRing<u8, 4>) -> Result<(), Box<dyn core::error::Error>> {
+            ring.set_limit(8)?;
+            Ok(())
+        }
+
+        let mut ring = FrodoRing::<u8, 4>::new();
+        let err = shrink(&mut ring).unwrap_err();
+        assert_eq!(err.to_string(), "limit exceeds ring capacity 4");
+    }
+}
@@ -0,0 +1,182 @@
+//! Счётчики работы очереди и их выгрузка в текстовом формате Prometheus.
+
+use crate::FrodoRing;
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает число отказов положить элемент из-за отсутствия места (с переполнением через ноль).
+    ///
+    /// Учитываются только элементы, возвращённые вызывающему: успешное вытеснение отказом не считается.
+    pub fn rejections(&self) -> u32 {
+        self.rejections
+    }
+
+    /// Возвращает число проведённых сжатий (с переполнением через ноль).
+    pub fn compactions(&self) -> u32 {
+        self.compactions
+    }
+
+    /// Возвращает наибольшую глубину очереди с момента её создания или последнего `reset_depth_max()`.
+    pub fn depth_max(&self) -> usize {
+        self.depth_max
+    }
+
+    /// Сбрасывает наибольшую глубину очереди до текущей, например, после очередного снятия метрик.
+    pub fn reset_depth_max(&mut self) {
+        self.depth_max = self.len();
+    }
+
+    /// Учитывает текущую глубину очереди в наибольшей после добавления элементов.
+    pub(crate) fn note_depth(&mut self) {
+        // Глубина не больше используемой ёмкости, так что пересчитывать её стоит, только если ёмкость выросла
+        if self.cap > self.depth_max {
+            self.depth_max = self.depth_max.max(self.len());
+        }
+    }
+
+    /// Дописывает значения метрик очереди в `out` в текстовом формате Prometheus с меткой `ring="<name>"`.
+    ///
+    /// Выгружаются глубина очереди и наибольшая глубина (см. `depth_max()`), использованные ячейки, ёмкость,
+    /// состояние верхнего порога заполненности, а также счётчики отказов и сжатий. Строки `# HELP` и `# TYPE` пишет `metrics_prometheus_header()`
+    /// один раз на всю выгрузку, так что метрики нескольких очередей можно дописывать подряд.
+    /// Символы `\`, `"` и перевод строки в `name` экранируются.
+    #[cfg(feature = "std")]
+    pub fn metrics_prometheus(&self, name: &str, out: &mut String) {
+        use core::fmt::Write;
+
+        let values = [
+            self.len() as u64,
+            self.depth_max as u64,
+            self.cap as u64,
+            self.limit as u64,
+            self.above_high_watermark() as u64,
+            self.rejections as u64,
+            self.compactions as u64,
+        ];
+
+        let mut label = String::with_capacity(name.len());
+        for ch in name.chars() {
+            match ch {
+                '\\' => label.push_str("\\\\"),
+                '"' => label.push_str("\\\""),
+                '\n' => label.push_str("\\n"),
+                ch => label.push(ch),
+            }
+        }
+
+        for ((metric, _, _), value) in METRICS.iter().zip(values) {
+            let _ = writeln!(out, "frodo_ring_{metric}{{ring=\"{label}\"}} {value}");
+        }
+    }
+}
+
+/// Метрики очереди: имя, тип и описание.
+#[cfg(feature = "std")]
+const METRICS: [(&str, &str, &str); 7] = [
+    ("depth", "gauge", "Elements in the queue."),
+    (
+        "depth_max",
+        "gauge",
+        "Peak elements in the queue since the last reset.",
+    ),
+    (
+        "used_cells",
+        "gauge",
+        "Cells spanned by the queue, including holes.",
+    ),
+    ("capacity", "gauge", "Logical capacity of the queue."),
+    (
+        "above_high_watermark",
+        "gauge",
+        "Whether the high watermark is reached.",
+    ),
+    (
+        "rejections_total",
+        "counter",
+        "Pushes rejected for lack of space.",
+    ),
+    ("compactions_total", "counter", "Compactions performed."),
+];
+
+/// Дописывает в `out` строки `# HELP` и `# TYPE` для метрик, выгружаемых `FrodoRing::metrics_prometheus()`.
+///
+/// Вызывается один раз перед выгрузкой метрик всех очередей.
+#[cfg(feature = "std")]
+pub fn metrics_prometheus_header(out: &mut String) {
+    use core::fmt::Write;
+
+    for (metric, kind, help) in METRICS {
+        let _ = writeln!(out, "# HELP frodo_ring_{metric} {help}");
+        let _ = writeln!(out, "# TYPE frodo_ring_{metric} {kind}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics() {
+        let mut ring = FrodoRing::<u8, 3>::new();
+        for i in 1..=3 {
+            ring.push(i).unwrap();
+        }
+        assert!(ring.push(4).is_err());
+        ring.remove_at(1);
+        ring.push(4).unwrap();
+        assert_eq!(ring.rejections(), 1);
+        assert_eq!(ring.compactions(), 1);
+
+        let mut out = String::new();
+        metrics_prometheus_header(&mut out);
+        ring.metrics_prometheus("uart", &mut out);
+        FrodoRing::<u8, 2>::new().metrics_prometheus("spi", &mut out);
+        assert_eq!(out.matches("# TYPE frodo_ring_depth gauge\n").count(), 1);
+        assert!(out.contains("frodo_ring_depth{ring=\"uart\"} 3\n"));
+        assert!(out.contains("frodo_ring_depth{ring=\"spi\"} 0\n"));
+        assert!(out.contains("frodo_ring_rejections_total{ring=\"uart\"} 1\n"));
+        assert!(out.contains("frodo_ring_above_high_watermark{ring=\"uart\"} 0\n"));
+        assert!(out.contains("frodo_ring_depth_max{ring=\"uart\"} 3\n"));
+        assert!(out.contains("frodo_ring_depth_max{ring=\"spi\"} 0\n"));
+
+        let mut out = String::new();
+        ring.metrics_prometheus("a\"b\\c\nd", &mut out);
+        assert!(out.starts_with("frodo_ring_depth{ring=\"a\\\"b\\\\c\\nd\"} 3\n"));
+    }
+
+    #[test]
+    fn depth_max() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        for i in 1..=3 {
+            ring.push(i).unwrap();
+        }
+        ring.pick();
+        ring.pick();
+        ring.push(4).unwrap();
+        assert_eq!(ring.depth_max(), 3);
+
+        ring.reset_depth_max();
+        assert_eq!(ring.depth_max(), 2);
+        ring.pick();
+        ring.push_slice(&[5, 6]);
+        assert_eq!(ring.depth_max(), 3);
+        assert_eq!(FrodoRing::<u8, 2>::fill(0).depth_max(), 2);
+    }
+
+    #[test]
+    fn rejections_count_returned_items() {
+        let mut ring = FrodoRing::<u8, 2>::new();
+        ring.push(1).unwrap();
+        ring.push(5).unwrap();
+        assert_eq!(ring.push_weighted(3, |el| *el), Ok(Some(1)));
+        assert_eq!(ring.push_weighted(2, |el| *el), Err(2));
+        assert_eq!(ring.rejections(), 1);
+
+        assert_eq!(ring.push_expiring(6, 4, |el| *el as u64), Ok(1));
+        assert_eq!(ring.rejections(), 1);
+
+        ring.set_eviction_priority(|el| *el);
+        assert!(ring.push(7).is_ok());
+        assert_eq!(ring.push(0), Err(0));
+        assert_eq!(ring.rejections(), 2);
+    }
+}
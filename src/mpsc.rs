@@ -0,0 +1,216 @@
+//! Очередь с несколькими производителями и одним потребителем.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Очередь, в которую кладут несколько производителей (прерывания, задачи), а забирает один диспетчер.
+///
+/// Каждая ячейка снабжена меткой круга: `2 * base` - ячейка свободна для записи на круге, начинающемся
+/// с позиции `base`, `2 * base + 1` - в ней лежит элемент этого круга. Производители захватывают позицию
+/// сравнением с обменом конца очереди, поэтому нужна атомарная операция CAS; потребитель единственный,
+/// и его начало очереди не атомарно.
+///
+/// Позиции и метки свободно переполняются, поэтому ёмкость `N` должна быть степенью двойки;
+/// другие значения отвергаются при компиляции.
+pub struct FrodoMpscRing<T, const N: usize> {
+    buffer: UnsafeCell<[MaybeUninit<T>; N]>,
+    stamps: [AtomicUsize; N],
+    /// Начало очереди; изменяется и читается только потребителем.
+    head: UnsafeCell<usize>,
+    /// Следующая позиция для захвата производителями.
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for FrodoMpscRing<T, N> {}
+
+impl<T, const N: usize> Default for FrodoMpscRing<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for FrodoMpscRing<T, N> {
+    fn drop(&mut self) {
+        while self.consumer_poll().is_some() {}
+    }
+}
+
+impl<T, const N: usize> FrodoMpscRing<T, N> {
+    /// Ёмкость, не являющаяся степенью двойки (в том числе нулевая), отвергается при компиляции.
+    const POWER_OF_TWO: () = assert!(
+        N.is_power_of_two(),
+        "FrodoMpscRing capacity N must be a power of two"
+    );
+
+    /// Создаёт новую очередь; может использоваться для инициализации `static`.
+    pub const fn new() -> Self {
+        let () = Self::POWER_OF_TWO;
+
+        Self {
+            buffer: UnsafeCell::new([const { MaybeUninit::uninit() }; N]),
+            stamps: [const { AtomicUsize::new(0) }; N],
+            head: UnsafeCell::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Возвращает метку свободной ячейки для позиции `pos`: удвоенное начало её круга.
+    fn free_stamp(pos: usize) -> usize {
+        (pos - pos % N).wrapping_mul(2)
+    }
+
+    /// Разделяет очередь на производителя, которого можно копировать, и единственного потребителя.
+    pub fn split(&mut self) -> (MpscProducer<'_, T, N>, MpscConsumer<'_, T, N>) {
+        (MpscProducer { ring: self }, MpscConsumer { ring: self })
+    }
+
+    fn producer_push(&self, item: T) -> Result<(), T> {
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let stamp = self.stamps[pos % N].load(Ordering::Acquire);
+            let free = Self::free_stamp(pos);
+
+            match stamp.wrapping_sub(free) as isize {
+                0 => match self.tail.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => break,
+                    Err(actual) => pos = actual,
+                },
+                // Ячейка ещё хранит элемент прошлого круга
+                diff if diff < 0 => return Err(item),
+                _ => pos = self.tail.load(Ordering::Relaxed),
+            }
+        }
+
+        unsafe { (*self.buffer.get())[pos % N].write(item) };
+        self.stamps[pos % N].store(Self::free_stamp(pos) + 1, Ordering::Release);
+        Ok(())
+    }
+
+    fn consumer_poll(&self) -> Option<T> {
+        let head = unsafe { &mut *self.head.get() };
+        if self.stamps[*head % N].load(Ordering::Acquire) != Self::free_stamp(*head) + 1 {
+            return None;
+        }
+
+        let item = unsafe { (*self.buffer.get())[*head % N].assume_init_read() };
+        let next_lap = Self::free_stamp(head.wrapping_add(N));
+        self.stamps[*head % N].store(next_lap, Ordering::Release);
+        *head = head.wrapping_add(1);
+        Some(item)
+    }
+}
+
+/// Сторона производителя `FrodoMpscRing`; дёшево копируется для каждого прерывания или задачи.
+pub struct MpscProducer<'ring, T, const N: usize> {
+    ring: &'ring FrodoMpscRing<T, N>,
+}
+
+impl<T, const N: usize> Clone for MpscProducer<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize> Copy for MpscProducer<'_, T, N> {}
+
+unsafe impl<T: Send, const N: usize> Send for MpscProducer<'_, T, N> {}
+
+impl<T, const N: usize> MpscProducer<'_, T, N> {
+    /// Кладёт элемент в очередь; возвращает его обратно, если очередь заполнена.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        self.ring.producer_push(item)
+    }
+}
+
+/// Сторона единственного потребителя `FrodoMpscRing`.
+pub struct MpscConsumer<'ring, T, const N: usize> {
+    ring: &'ring FrodoMpscRing<T, N>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for MpscConsumer<'_, T, N> {}
+
+impl<T, const N: usize> MpscConsumer<'_, T, N> {
+    /// Забирает первый опубликованный элемент очереди, если он есть.
+    pub fn poll(&mut self) -> Option<T> {
+        self.ring.consumer_poll()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mpsc_ring() {
+        let mut ring = FrodoMpscRing::<u32, 4>::new();
+        let (producer, mut consumer) = ring.split();
+
+        assert_eq!(consumer.poll(), None);
+        for i in 0..4 {
+            assert!(producer.push(i).is_ok());
+        }
+        assert_eq!(producer.push(4), Err(4));
+        assert_eq!(consumer.poll(), Some(0));
+        assert!(producer.push(4).is_ok());
+        for i in 1..5 {
+            assert_eq!(consumer.poll(), Some(i));
+        }
+        assert_eq!(consumer.poll(), None);
+    }
+
+    #[test]
+    fn mpsc_ring_counter_wrap() {
+        let mut ring = FrodoMpscRing::<u32, 4>::new();
+        let start = usize::MAX - 1;
+        ring.head = UnsafeCell::new(start);
+        ring.tail = AtomicUsize::new(start);
+        for pos in start..=usize::MAX {
+            ring.stamps[pos % 4] = AtomicUsize::new(FrodoMpscRing::<u32, 4>::free_stamp(pos));
+        }
+        let (producer, mut consumer) = ring.split();
+
+        for round in 0..3 {
+            for i in 0..4 {
+                assert!(producer.push(round * 4 + i).is_ok());
+            }
+            assert_eq!(producer.push(0xFF), Err(0xFF));
+            for i in 0..4 {
+                assert_eq!(consumer.poll(), Some(round * 4 + i));
+            }
+            assert_eq!(consumer.poll(), None);
+        }
+    }
+
+    #[test]
+    fn mpsc_ring_threads() {
+        let mut ring = FrodoMpscRing::<u32, 8>::new();
+        let (producer, mut consumer) = ring.split();
+
+        std::thread::scope(|s| {
+            for id in 0..3u32 {
+                s.spawn(move || {
+                    for i in 0..1000 {
+                        while producer.push((id << 16) | i).is_err() {
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+            }
+
+            let mut next = [0u32; 3];
+            while next.iter().any(|n| *n < 1000) {
+                if let Some(item) = consumer.poll() {
+                    let id = (item >> 16) as usize;
+                    assert_eq!(item & 0xFFFF, next[id]);
+                    next[id] += 1;
+                }
+            }
+        });
+    }
+}
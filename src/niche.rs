@@ -0,0 +1,243 @@
+//! Кольцевая очередь без массива занятости: пустота ячейки кодируется в самом значении.
+
+use core::num::{
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64,
+    NonZeroUsize,
+};
+
+/// Тип, у которого есть значение, никогда не встречающееся среди элементов и потому обозначающее пустую ячейку.
+///
+/// Для `NonZero*` таким значением служит ноль; для своих типов достаточно выбрать неиспользуемое значение
+/// в качестве `EMPTY` с `Cell = Self`.
+pub trait Sentinel: Copy {
+    /// Представление ячейки буфера.
+    type Cell: Copy;
+
+    /// Содержимое пустой ячейки.
+    const EMPTY: Self::Cell;
+
+    /// Кодирует элемент в ячейку; результат не должен совпадать с `EMPTY`.
+    fn into_cell(self) -> Self::Cell;
+
+    /// Декодирует ячейку; для `EMPTY` возвращает `None`.
+    fn from_cell(cell: Self::Cell) -> Option<Self>;
+}
+
+macro_rules! impl_sentinel_nonzero {
+    ($($nz:ty => $int:ty),*) => {
+        $(
+            impl Sentinel for $nz {
+                type Cell = $int;
+                const EMPTY: $int = 0;
+
+                fn into_cell(self) -> $int {
+                    self.get()
+                }
+
+                fn from_cell(cell: $int) -> Option<Self> {
+                    <$nz>::new(cell)
+                }
+            }
+        )*
+    };
+}
+
+impl_sentinel_nonzero!(
+    NonZeroU8 => u8, NonZeroU16 => u16, NonZeroU32 => u32, NonZeroU64 => u64, NonZeroUsize => usize,
+    NonZeroI8 => i8, NonZeroI16 => i16, NonZeroI32 => i32, NonZeroI64 => i64
+);
+
+/// Кольцевая очередь с пропусками, как `FrodoRing`, но без отдельного массива занятости.
+///
+/// Занимает ровно `N` ячеек `T::Cell`, что для небольших элементов вдвое экономнее `FrodoRing`.
+/// Элементы возвращаются по значению, поскольку хранятся в закодированном виде.
+pub struct FrodoNicheRing<T: Sentinel, const N: usize> {
+    buffer: [T::Cell; N],
+    head: usize,
+    cap: usize,
+}
+
+impl<T: Sentinel, const N: usize> Default for FrodoNicheRing<T, N> {
+    fn default() -> Self {
+        Self {
+            buffer: [T::EMPTY; N],
+            head: 0,
+            cap: 0,
+        }
+    }
+}
+
+impl<T: Sentinel, const N: usize> FrodoNicheRing<T, N> {
+    /// Создаёт новую кольцевую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn real_pos(&self, naive_pos: usize) -> usize {
+        (self.head + naive_pos) % N
+    }
+
+    fn is_occupied(&self, real_pos: usize) -> bool {
+        T::from_cell(self.buffer[real_pos]).is_some()
+    }
+
+    fn checked_real_pos(&self, naive_pos: isize) -> Option<usize> {
+        if naive_pos >= self.cap as isize || naive_pos < -(self.cap as isize) {
+            return None;
+        }
+
+        Some(if naive_pos >= 0 {
+            self.real_pos(naive_pos as usize)
+        } else {
+            (self.head + self.cap + N - (-naive_pos) as usize) % N
+        })
+    }
+
+    /// Возвращает использованное число ячеек кольцевой очереди.
+    pub fn used(&self) -> usize {
+        self.cap
+    }
+
+    /// Возвращает число элементов, находящихся в очереди.
+    pub fn len(&self) -> usize {
+        (0..self.cap)
+            .filter(|n| self.is_occupied(self.real_pos(*n)))
+            .count()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.cap == 0
+    }
+
+    /// Добавляет элемент в конец очереди, при необходимости ужимая пропуски.
+    ///
+    /// Возвращает элемент обратно, если места нет или он кодируется в пустое значение.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let cell = item.into_cell();
+        if T::from_cell(cell).is_none() {
+            return Err(item);
+        }
+
+        if self.cap == N {
+            self.compact();
+            if self.cap == N {
+                return Err(item);
+            }
+        }
+
+        let real_pos = self.real_pos(self.cap);
+        self.buffer[real_pos] = cell;
+        self.cap += 1;
+        Ok(())
+    }
+
+    /// Получает элемент по ячейке (наивной позиции); отрицательные позиции отсчитываются с конца.
+    pub fn at(&self, naive_pos: isize) -> Option<T> {
+        T::from_cell(self.buffer[self.checked_real_pos(naive_pos)?])
+    }
+
+    /// Удаляет содержимое ячейки, находящейся по наивной позиции, и возвращает его.
+    pub fn remove_at(&mut self, naive_pos: isize) -> Option<T> {
+        let real_pos = self.checked_real_pos(naive_pos)?;
+        let item = T::from_cell(self.buffer[real_pos])?;
+        self.buffer[real_pos] = T::EMPTY;
+        self.trim();
+        Some(item)
+    }
+
+    /// Изымает первый элемент очереди.
+    pub fn pick(&mut self) -> Option<T> {
+        self.remove_at(0)
+    }
+
+    /// Создаёт итератор по элементам очереди.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.cap).filter_map(|n| T::from_cell(self.buffer[self.real_pos(n)]))
+    }
+
+    fn trim(&mut self) {
+        while self.cap > 0 && !self.is_occupied(self.head) {
+            self.head = (self.head + 1) % N;
+            self.cap -= 1;
+        }
+        while self.cap > 0 && !self.is_occupied(self.real_pos(self.cap - 1)) {
+            self.cap -= 1;
+        }
+    }
+
+    /// Сдвигает элементы к началу очереди, убирая пропуски.
+    fn compact(&mut self) {
+        let mut write_pos = 0usize;
+        for read_pos in 0..self.cap {
+            let read_real_pos = self.real_pos(read_pos);
+            if !self.is_occupied(read_real_pos) {
+                continue;
+            }
+            if read_pos != write_pos {
+                let write_real_pos = self.real_pos(write_pos);
+                self.buffer[write_real_pos] = self.buffer[read_real_pos];
+                self.buffer[read_real_pos] = T::EMPTY;
+            }
+            write_pos += 1;
+        }
+        self.cap = write_pos;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn niche_ring() {
+        let mut ring = FrodoNicheRing::<NonZeroU32, 4>::new();
+        assert_eq!(core::mem::size_of_val(&ring.buffer), 16);
+
+        for i in 1..=4 {
+            ring.push(NonZeroU32::new(i).unwrap()).unwrap();
+        }
+        assert!(ring.push(NonZeroU32::new(5).unwrap()).is_err());
+
+        assert_eq!(ring.remove_at(1).map(NonZeroU32::get), Some(2));
+        assert_eq!(ring.at(1), None);
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.used(), 4);
+
+        ring.push(NonZeroU32::new(5).unwrap()).unwrap();
+        assert_eq!(
+            ring.iter().map(NonZeroU32::get).collect::<Vec<_>>(),
+            [1, 3, 4, 5]
+        );
+        assert_eq!(ring.at(-1).map(NonZeroU32::get), Some(5));
+
+        assert_eq!(ring.pick().map(NonZeroU32::get), Some(1));
+        assert_eq!(ring.remove_at(-1).map(NonZeroU32::get), Some(5));
+        assert_eq!(ring.iter().map(NonZeroU32::get).collect::<Vec<_>>(), [3, 4]);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Task(u8);
+
+    impl Sentinel for Task {
+        type Cell = Task;
+        const EMPTY: Task = Task(u8::MAX);
+
+        fn into_cell(self) -> Task {
+            self
+        }
+
+        fn from_cell(cell: Task) -> Option<Task> {
+            (cell != Self::EMPTY).then_some(cell)
+        }
+    }
+
+    #[test]
+    fn niche_ring_user_sentinel() {
+        let mut ring = FrodoNicheRing::<Task, 2>::new();
+        assert_eq!(ring.push(Task::EMPTY), Err(Task::EMPTY));
+        ring.push(Task(1)).unwrap();
+        assert_eq!(ring.pick(), Some(Task(1)));
+        assert!(ring.is_empty());
+    }
+}
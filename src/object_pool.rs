@@ -0,0 +1,110 @@
+//! Пул объектов без аллокаций поверх кольцевой очереди.
+
+use core::cell::RefCell;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+use crate::FrodoRing;
+
+/// Пул свободных объектов (буферов, дескрипторов), хранящихся в кольцевой очереди.
+///
+/// `acquire()` выдаёт объект во владение через `FrodoPooled`; при удалении охранника объект возвращается в пул.
+pub struct FrodoObjectPool<T, const N: usize> {
+    free: RefCell<FrodoRing<T, N>>,
+}
+
+impl<T, const N: usize> Default for FrodoObjectPool<T, N> {
+    fn default() -> Self {
+        Self {
+            free: RefCell::new(FrodoRing::new()),
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoObjectPool<T, N> {
+    /// Создаёт пустой пул.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Кладёт объект в пул; возвращает его обратно, если пул заполнен.
+    pub fn put(&self, item: T) -> Result<(), T> {
+        self.free.borrow_mut().push(item)
+    }
+
+    /// Возвращает число свободных объектов в пуле.
+    pub fn available(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    /// Выдаёт свободный объект из пула.
+    pub fn acquire(&self) -> Option<FrodoPooled<'_, T, N>> {
+        let item = self.free.borrow_mut().pick()?;
+        Some(FrodoPooled {
+            pool: self,
+            item: ManuallyDrop::new(item),
+        })
+    }
+}
+
+/// Объект, выданный пулом; при удалении возвращается в пул.
+pub struct FrodoPooled<'pool, T, const N: usize> {
+    pool: &'pool FrodoObjectPool<T, N>,
+    item: ManuallyDrop<T>,
+}
+
+impl<T, const N: usize> FrodoPooled<'_, T, N> {
+    /// Забирает объект из-под управления пула насовсем.
+    pub fn into_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        unsafe { ManuallyDrop::take(&mut this.item) }
+    }
+}
+
+impl<T, const N: usize> Deref for FrodoPooled<'_, T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.item
+    }
+}
+
+impl<T, const N: usize> DerefMut for FrodoPooled<'_, T, N> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.item
+    }
+}
+
+impl<T, const N: usize> Drop for FrodoPooled<'_, T, N> {
+    fn drop(&mut self) {
+        let item = unsafe { ManuallyDrop::take(&mut self.item) };
+        let _ = self.pool.put(item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_pool() {
+        let pool = FrodoObjectPool::<[u8; 4], 2>::new();
+        assert!(pool.put([0; 4]).is_ok());
+        assert!(pool.put([0; 4]).is_ok());
+        assert!(pool.put([0; 4]).is_err());
+
+        let mut a = pool.acquire().unwrap();
+        a[0] = 0x1;
+        let b = pool.acquire().unwrap();
+        assert!(pool.acquire().is_none());
+        assert_eq!(pool.available(), 0);
+
+        drop(a);
+        assert_eq!(pool.available(), 1);
+        assert_eq!(pool.acquire().unwrap()[0], 0x1);
+
+        let owned = b.into_inner();
+        assert_eq!(owned, [0; 4]);
+        assert_eq!(pool.available(), 1);
+    }
+}
@@ -0,0 +1,75 @@
+//! Охранник первого элемента с изъятием только после подтверждения.
+
+use core::ops::{Deref, DerefMut};
+
+use crate::FrodoRing;
+
+/// Охранник, дающий доступ к первому элементу очереди.
+///
+/// `commit()` изымает элемент; если охранник удалён без `commit()`, элемент остаётся в очереди.
+/// Это позволяет изымать элемент только после его успешной обработки.
+pub struct FrodoPeekGuard<'ring, T, const N: usize> {
+    ring: &'ring mut FrodoRing<T, N>,
+    real_pos: usize,
+}
+
+impl<T, const N: usize> FrodoPeekGuard<'_, T, N> {
+    /// Изымает элемент из очереди и возвращает его.
+    pub fn commit(self) -> T {
+        self.ring.take(self.real_pos)
+    }
+}
+
+impl<T, const N: usize> Deref for FrodoPeekGuard<'_, T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.ring.buffer[self.real_pos].assume_init_ref() }
+    }
+}
+
+impl<T, const N: usize> DerefMut for FrodoPeekGuard<'_, T, N> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ring.buffer[self.real_pos].assume_init_mut() }
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает охранника первого элемента очереди, не изымая его.
+    pub fn pick_guard(&mut self) -> Option<FrodoPeekGuard<'_, T, N>> {
+        let real_pos = (0..self.cap)
+            .map(|naive_pos| self.real_pos(naive_pos))
+            .find(|real_pos| self.occupied[*real_pos])?;
+        Some(FrodoPeekGuard {
+            ring: self,
+            real_pos,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_guard() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        assert!(ring.pick_guard().is_none());
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+
+        {
+            let mut guard = ring.pick_guard().unwrap();
+            assert_eq!(*guard, 0x1);
+            *guard += 0x10;
+        }
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.at(0), Some(&0x11));
+
+        let guard = ring.pick_guard().unwrap();
+        assert_eq!(guard.commit(), 0x11);
+        assert_eq!(ring.pick_guard().map(|guard| guard.commit()), Some(0x2));
+        assert!(ring.is_empty());
+    }
+}
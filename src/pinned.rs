@@ -0,0 +1,145 @@
+//! Очередь для элементов, которые нельзя перемещать (`!Unpin`).
+
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+
+use crate::{FrodoRing, FrodoRingIterator};
+
+/// Очередь, элементы которой никогда не перемещаются после добавления.
+///
+/// Подходит для футур и самоссылающихся автоматов протоколов. Сжатие отключено навсегда, а операций,
+/// перемещающих элементы (изъятие по значению, сдвиги, перемешивание), у типа нет: элементы удаляются на месте.
+/// Сама очередь должна быть закреплена (`core::pin::pin!` или `static`), поэтому методы принимают `Pin<&mut Self>`.
+pub struct FrodoPinRing<T, const N: usize> {
+    ring: FrodoRing<T, N>,
+    _pinned: PhantomPinned,
+}
+
+impl<T, const N: usize> Default for FrodoPinRing<T, N> {
+    fn default() -> Self {
+        let mut ring = FrodoRing::new();
+        // Закрепление навсегда: очередь никогда не сжимается и не сдвигает элементы
+        ring.pins = 1;
+        Self {
+            ring,
+            _pinned: PhantomPinned,
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoPinRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn ring_mut(self: Pin<&mut Self>) -> &mut FrodoRing<T, N> {
+        // Безопасно: ни один из методов не перемещает элементы очереди
+        unsafe { &mut self.get_unchecked_mut().ring }
+    }
+
+    /// Возвращает использованное число ячеек.
+    pub fn used(&self) -> usize {
+        self.ring.used()
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Кладёт элемент в очередь и возвращает закреплённую ссылку на него.
+    ///
+    /// Пустые ячейки в середине очереди не переиспользуются: место освобождается, лишь когда начало очереди проходит через них.
+    pub fn push_pinned(self: Pin<&mut Self>, item: T) -> Result<Pin<&mut T>, T> {
+        let ring = self.ring_mut();
+        let Some(real_pos) = ring.free_cell() else {
+            return Err(item);
+        };
+        ring.buffer[real_pos].write(item);
+        ring.fill_cell();
+        Ok(unsafe { Pin::new_unchecked(ring.buffer[real_pos].assume_init_mut()) })
+    }
+
+    /// Получает элемент по ячейке (наивной позиции).
+    pub fn at(&self, naive_pos: isize) -> Option<&T> {
+        self.ring.at(naive_pos)
+    }
+
+    /// Получает закреплённую изменяемую ссылку на элемент по ячейке (наивной позиции).
+    pub fn at_mut(self: Pin<&mut Self>, naive_pos: isize) -> Option<Pin<&mut T>> {
+        let ring = self.ring_mut();
+        let real_pos = ring.checked_real_pos(naive_pos)?;
+        if !ring.occupied[real_pos] {
+            return None;
+        }
+        Some(unsafe { Pin::new_unchecked(ring.buffer[real_pos].assume_init_mut()) })
+    }
+
+    /// Создаёт итератор по очереди.
+    pub fn iter(&self) -> FrodoRingIterator<'_, T, N> {
+        self.ring.iter()
+    }
+
+    /// Удаляет элемент в ячейке по наивной позиции на месте. Возвращает `false`, если ячейка пуста.
+    pub fn drop_at(self: Pin<&mut Self>, naive_pos: isize) -> bool {
+        let ring = self.ring_mut();
+        let Some(real_pos) = ring.checked_real_pos(naive_pos) else {
+            return false;
+        };
+        if !ring.occupied[real_pos] {
+            return false;
+        }
+
+        ring.occupied[real_pos] = false;
+        ring.trim();
+        unsafe { ring.buffer[real_pos].assume_init_drop() };
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pin_ring() {
+        let mut ring = core::pin::pin!(FrodoPinRing::<PhantomPinned, 2>::new());
+
+        let first = ring.as_mut().push_pinned(PhantomPinned).unwrap();
+        let first_addr = &*first as *const PhantomPinned;
+        assert!(ring.as_mut().push_pinned(PhantomPinned).is_ok());
+        assert!(ring.as_mut().push_pinned(PhantomPinned).is_err());
+
+        assert!(ring.as_mut().drop_at(1));
+        assert_eq!(ring.len(), 1);
+        assert!(ring.as_mut().push_pinned(PhantomPinned).is_ok());
+        assert_eq!(ring.at(0).unwrap() as *const PhantomPinned, first_addr);
+
+        assert!(ring.as_mut().drop_at(1));
+        assert!(ring.as_mut().drop_at(0));
+        assert!(!ring.as_mut().drop_at(0));
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn pin_ring_holes() {
+        let mut ring = core::pin::pin!(FrodoPinRing::<u8, 3>::new());
+
+        assert!(ring.as_mut().push_pinned(0x1).is_ok());
+        assert!(ring.as_mut().push_pinned(0x2).is_ok());
+        assert!(ring.as_mut().push_pinned(0x3).is_ok());
+        assert!(ring.as_mut().drop_at(1));
+        assert_eq!(ring.as_mut().push_pinned(0x4).err(), Some(0x4));
+
+        *ring.as_mut().at_mut(2).unwrap() += 0x10;
+        assert!(ring.as_mut().drop_at(0));
+        assert!(ring.as_mut().push_pinned(0x4).is_ok());
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0x13, 0x4]);
+    }
+}
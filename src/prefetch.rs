@@ -0,0 +1,36 @@
+//! Подсказки предвыборки ячеек для элементов во внешней памяти (при включённой функции `prefetch`).
+
+use core::mem::MaybeUninit;
+
+/// Просит процессор заранее подгрузить в кэш ячейку по указателю.
+///
+/// Указатель может выходить за пределы буфера: предвыборка не обращается к памяти и не вызывает сбоев.
+/// Без функции `prefetch` и на архитектурах без поддержки ничего не делает.
+#[inline(always)]
+pub(crate) fn prefetch<T>(cell: *const MaybeUninit<T>) {
+    #[cfg(all(feature = "prefetch", target_arch = "x86_64"))]
+    unsafe {
+        use core::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+        _mm_prefetch::<_MM_HINT_T0>(cell as *const i8);
+    }
+
+    #[cfg(all(feature = "prefetch", target_arch = "x86", target_feature = "sse"))]
+    unsafe {
+        use core::arch::x86::{_MM_HINT_T0, _mm_prefetch};
+        _mm_prefetch::<_MM_HINT_T0>(cell as *const i8);
+    }
+
+    #[cfg(all(feature = "prefetch", target_arch = "aarch64"))]
+    unsafe {
+        core::arch::asm!("prfm pldl1keep, [{0}]", in(reg) cell, options(nostack, readonly, preserves_flags));
+    }
+
+    // PLD есть в ARMv5TE и новее, но в Thumb-наборе только начиная с ARMv7, поэтому проверяется признак `v7`
+    #[cfg(all(feature = "prefetch", target_arch = "arm", target_feature = "v7"))]
+    unsafe {
+        core::arch::asm!("pld [{0}]", in(reg) cell, options(nostack, readonly, preserves_flags));
+    }
+
+    // На остальных архитектурах (например, RISC-V и Xtensa) указатель не используется
+    let _ = cell;
+}
@@ -0,0 +1,134 @@
+//! Очередь с приоритетами и старением элементов.
+
+use crate::FrodoRing;
+
+/// Очередь, каждый элемент которой снабжён приоритетом; `pick()` выдаёт элемент с наибольшим приоритетом.
+///
+/// Если задан шаг старения (`with_aging()`), каждый элемент получает +1 к приоритету за каждые `aging`
+/// выдач, в которых его обошли, так что фоновые элементы не голодают под постоянной нагрузкой
+/// высокоприоритетных. Элементы с одинаковым действующим приоритетом выдаются в порядке FIFO.
+pub struct FrodoPriority<T, const N: usize> {
+    /// Элементы с приоритетом и числом обходов.
+    ring: FrodoRing<(u8, u16, T), N>,
+    aging: u16,
+}
+
+impl<T, const N: usize> Default for FrodoPriority<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            aging: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoPriority<T, N> {
+    /// Создаёт новую очередь без старения.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Создаёт новую очередь, поднимающую приоритет элемента на 1 за каждые `aging` обходов.
+    ///
+    /// `aging == 0` отключает старение.
+    pub fn with_aging(aging: u16) -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            aging,
+        }
+    }
+
+    /// Действующий приоритет элемента с учётом старения.
+    fn effective(&self, priority: u8, skipped: u16) -> u32 {
+        match self.aging {
+            0 => priority as u32,
+            aging => priority as u32 + (skipped / aging) as u32,
+        }
+    }
+
+    /// Находит наивную позицию элемента с наибольшим действующим приоритетом.
+    fn highest(&self) -> Option<isize> {
+        let mut highest: Option<(isize, u32)> = None;
+
+        for naive_pos in 0..self.ring.used() as isize {
+            if let Some((priority, skipped, _)) = self.ring.at(naive_pos) {
+                let effective = self.effective(*priority, *skipped);
+                if highest.is_none_or(|(_, h)| effective > h) {
+                    highest = Some((naive_pos, effective));
+                }
+            }
+        }
+
+        highest.map(|(naive_pos, _)| naive_pos)
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Кладёт элемент с приоритетом `priority` в очередь.
+    pub fn push(&mut self, priority: u8, item: T) -> Result<(), T> {
+        self.ring
+            .push((priority, 0, item))
+            .map_err(|(_, _, item)| item)
+    }
+
+    /// Возвращает элемент, который будет выдан следующим, вместе с его действующим приоритетом.
+    pub fn peek(&self) -> Option<(u32, &T)> {
+        let (priority, skipped, item) = self.ring.at(self.highest()?)?;
+        Some((self.effective(*priority, *skipped), item))
+    }
+
+    /// Отдаёт элемент с наибольшим действующим приоритетом; остальные элементы стареют.
+    pub fn pick(&mut self) -> Option<T> {
+        let naive_pos = self.highest()?;
+        let (_, _, item) = self.ring.remove_at(naive_pos)?;
+
+        if self.aging > 0 {
+            self.ring
+                .map_in_place(|(_, skipped, _)| *skipped = skipped.saturating_add(1));
+        }
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_without_aging() {
+        let mut queue = FrodoPriority::<u8, 4>::new();
+        queue.push(1, 0x1).unwrap();
+        queue.push(5, 0x2).unwrap();
+        queue.push(5, 0x3).unwrap();
+
+        assert_eq!(queue.peek(), Some((5, &0x2)));
+        assert_eq!(queue.pick(), Some(0x2));
+        queue.push(7, 0x4).unwrap();
+        assert_eq!(queue.pick(), Some(0x4));
+        assert_eq!(queue.pick(), Some(0x3));
+        assert_eq!(queue.pick(), Some(0x1));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn priority_aging() {
+        let mut queue = FrodoPriority::<u8, 4>::with_aging(2);
+        queue.push(0, 0xB).unwrap();
+
+        // Под постоянной нагрузкой с приоритетом 2 фоновый элемент выдаётся после 4 обходов
+        let mut picked = [0u8; 5];
+        for slot in picked.iter_mut() {
+            queue.push(2, 0xF).unwrap();
+            *slot = queue.pick().unwrap();
+        }
+        assert_eq!(picked, [0xF, 0xF, 0xF, 0xF, 0xB]);
+    }
+}
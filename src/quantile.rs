@@ -0,0 +1,55 @@
+//! Процентили по текущему содержимому очереди.
+
+use core::cmp::Ordering;
+use core::mem::MaybeUninit;
+
+use crate::FrodoRing;
+
+impl<T: Copy + PartialOrd, const N: usize> FrodoRing<T, N> {
+    /// Возвращает `p`-й процентиль (`0..=100`) элементов очереди методом ближайшего ранга.
+    ///
+    /// Элементы копируются в рабочий массив на стеке (`N` элементов) и выбираются на месте за `O(n)` в среднем,
+    /// без выделения памяти. Несравнимые значения (например, NaN) считаются равными. Возвращает `None`
+    /// для пустой очереди или `p > 100`.
+    pub fn percentile(&self, p: u8) -> Option<T> {
+        if p > 100 || self.is_empty() {
+            return None;
+        }
+
+        let mut scratch = [const { MaybeUninit::<T>::uninit() }; N];
+        let mut len = 0;
+        for item in self.iter() {
+            scratch[len].write(*item);
+            len += 1;
+        }
+
+        let samples =
+            unsafe { core::slice::from_raw_parts_mut(scratch.as_mut_ptr().cast::<T>(), len) };
+        let rank = (p as usize * len).div_ceil(100).max(1);
+        let (_, nth, _) = samples
+            .select_nth_unstable_by(rank - 1, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        Some(*nth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile() {
+        let mut ring = FrodoRing::<u32, 8>::new();
+        assert_eq!(ring.percentile(50), None);
+
+        for latency in [15, 20, 35, 40, 50] {
+            assert!(ring.push(latency).is_ok());
+        }
+        assert_eq!(ring.percentile(0), Some(15));
+        assert_eq!(ring.percentile(30), Some(20));
+        assert_eq!(ring.percentile(40), Some(20));
+        assert_eq!(ring.percentile(50), Some(35));
+        assert_eq!(ring.percentile(100), Some(50));
+        assert_eq!(ring.percentile(101), None);
+        assert!(ring.iter().eq([&15, &20, &35, &40, &50]));
+    }
+}
@@ -0,0 +1,155 @@
+//! Операции со случайным выбором элементов.
+//!
+//! Источником случайности служит любая функция, выдающая равномерно распределённые `u32`;
+//! для генераторов `rand_core::RngCore` достаточно передать `|| rng.next_u32()`.
+
+use crate::FrodoRing;
+
+/// Возвращает равномерно распределённое число в диапазоне `[0, bound)`.
+pub(crate) fn random_below<R: FnMut() -> u32>(rng: &mut R, bound: usize) -> usize {
+    ((rng() as u64 * bound as u64) >> 32) as usize
+}
+
+/// Возвращает равномерно распределённое число в диапазоне `[0, bound)` для границ шире `u32`.
+///
+/// Берёт из источника два `u32`, составляя 64-битное число, и масштабирует его без усечения.
+pub(crate) fn random_below_u64<R: FnMut() -> u32>(rng: &mut R, bound: u64) -> u64 {
+    let x = ((rng() as u64) << 32) | rng() as u64;
+    ((x as u128 * bound as u128) >> 64) as u64
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает случайный элемент очереди, не изымая его.
+    pub fn peek_random<R: FnMut() -> u32>(&self, mut rng: R) -> Option<&T> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+
+        self.get(random_below(&mut rng, len))
+    }
+
+    /// Отдаёт случайный элемент, изымая его из очереди.
+    pub fn pick_random<R: FnMut() -> u32>(&mut self, mut rng: R) -> Option<T> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+
+        self.remove(random_below(&mut rng, len))
+    }
+
+    /// Перемешивает элементы очереди (алгоритм Фишера-Йетса), не меняя расположения пустых ячеек.
+    ///
+    /// Дополнительной памяти не требуется; если между элементами есть пустые ячейки, перемешивание занимает O(n²).
+    ///
+    /// Возвращает `false` и ничего не меняет, если элементы закреплены и не могут перемещаться.
+    pub fn shuffle<R: FnMut() -> u32>(&mut self, mut rng: R) -> bool {
+        if self.is_pinned() {
+            return false;
+        }
+
+        // Ячейки перебираются с конца без массива индексов на стеке; ячейка случайного партнёра
+        // находится обходом очереди, если в ней есть пустоты, и вычисляется сразу, если их нет
+        let len = self.len();
+        let dense = len == self.cap;
+        let mut naive_pos = self.cap;
+        for i in (1..len).rev() {
+            naive_pos -= 1;
+            while !self.occupied[self.real_pos(naive_pos)] {
+                naive_pos -= 1;
+            }
+            let cell = self.real_pos(naive_pos);
+
+            let j = random_below(&mut rng, i + 1);
+            let other = if dense {
+                self.real_pos(j)
+            } else {
+                (0..naive_pos)
+                    .map(|pos| self.real_pos(pos))
+                    .filter(|cell| self.occupied[*cell])
+                    .nth(j)
+                    .unwrap_or(cell)
+            };
+            self.buffer.swap(cell, other);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Простой детерминированный генератор для тестов.
+    fn xorshift(mut state: u32) -> impl FnMut() -> u32 {
+        move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        }
+    }
+
+    #[test]
+    fn random() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+        assert_eq!(ring.peek_random(xorshift(1)), None);
+        assert_eq!(ring.pick_random(xorshift(1)), None);
+
+        for i in 0x1..=0x8 {
+            assert!(ring.push(i).is_ok());
+        }
+        assert_eq!(ring.remove_at(3), Some(0x4));
+
+        let mut rng = xorshift(7);
+        let peeked = *ring.peek_random(&mut rng).unwrap();
+        assert!(ring.any(|el| *el == peeked));
+
+        let picked = ring.pick_random(&mut rng).unwrap();
+        assert!(!ring.any(|el| *el == picked));
+        assert_eq!(ring.len(), 6);
+
+        let before = ring.iter().copied().collect::<Vec<_>>();
+        let slot = ring.reserve().unwrap();
+        assert!(!ring.shuffle(&mut rng));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), before);
+        ring.abort_slot(slot);
+
+        let holes = |ring: &FrodoRing<u8, 8>| {
+            (0..ring.used() as isize)
+                .map(|pos| ring.at(pos).is_none())
+                .collect::<Vec<_>>()
+        };
+        let before = holes(&ring);
+        assert!(before.contains(&true));
+        assert!(ring.shuffle(&mut rng));
+        assert_eq!(ring.len(), 6);
+        assert_eq!(holes(&ring), before);
+        let mut items = ring.iter().copied().collect::<Vec<_>>();
+        items.sort();
+        let mut expected = [0x1, 0x2, 0x3, 0x5, 0x6, 0x7, 0x8]
+            .into_iter()
+            .filter(|el| *el != picked)
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn shuffle_dense() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+        for i in 0x1..=0x6 {
+            assert!(ring.push(i).is_ok());
+        }
+        ring.pick();
+        assert!(ring.push(0x7).is_ok());
+
+        assert!(ring.shuffle(xorshift(3)));
+        let mut items = ring.iter().copied().collect::<Vec<_>>();
+        assert_ne!(items, [0x2, 0x3, 0x4, 0x5, 0x6, 0x7]);
+        items.sort();
+        assert_eq!(items, [0x2, 0x3, 0x4, 0x5, 0x6, 0x7]);
+        assert_eq!(ring.check_invariants(), Ok(()));
+    }
+}
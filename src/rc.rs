@@ -0,0 +1,195 @@
+//! Разделяемые ссылки на элементы очереди со счётчиками в ячейках.
+
+use crate::FrodoRing;
+
+/// Разделяемая ссылка на элемент `FrodoRcRing`.
+///
+/// Не хранит заимствования очереди: пока ссылка жива, элемент нельзя изъять, а ячейки очереди не перемещаются.
+/// Каждая ссылка должна быть возвращена через `FrodoRcRing::release()`; забытая ссылка навсегда закрепляет ячейку.
+///
+/// Ссылка помечена идентификатором выдавшей её очереди: другая очередь её не принимает и не меняет своих счётчиков.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FrodoRef {
+    ring: usize,
+    real_pos: usize,
+}
+
+/// Очередь со счётчиком разделяемых ссылок на каждую ячейку.
+///
+/// Даёт совместное владение элементами очереди в духе `Rc` без аллокаций.
+pub struct FrodoRcRing<T, const N: usize> {
+    ring: FrodoRing<T, N>,
+    refs: [u16; N],
+    /// Идентификатор очереди, которым помечаются её ссылки.
+    id: usize,
+}
+
+impl<T, const N: usize> Default for FrodoRcRing<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            refs: [0; N],
+            id: crate::next_instance_id(),
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoRcRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает очередь для чтения.
+    pub fn ring(&self) -> &FrodoRing<T, N> {
+        &self.ring
+    }
+
+    /// Кладёт элемент в очередь.
+    ///
+    /// Пока есть живые ссылки, очередь не проводит сжатие, поэтому пустые ячейки в середине не переиспользуются.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        self.ring.push(item)
+    }
+
+    /// Изымает содержимое ячейки по наивной позиции, если на неё нет живых ссылок.
+    pub fn remove_at(&mut self, naive_pos: isize) -> Option<T> {
+        let real_pos = self.ring.checked_real_pos(naive_pos)?;
+        if self.refs[real_pos] > 0 {
+            return None;
+        }
+        self.ring.remove_at(naive_pos)
+    }
+
+    /// Отдаёт первый элемент, изымая его из очереди, если на него нет живых ссылок.
+    pub fn pick(&mut self) -> Option<T> {
+        let naive_pos =
+            (0..self.ring.used()).find(|naive_pos| self.ring.at(*naive_pos as isize).is_some())?;
+        self.remove_at(naive_pos as isize)
+    }
+
+    /// Возвращает число живых ссылок на ячейку по наивной позиции.
+    pub fn ref_count(&self, naive_pos: isize) -> u16 {
+        self.ring
+            .checked_real_pos(naive_pos)
+            .map_or(0, |real_pos| self.refs[real_pos])
+    }
+
+    /// Создаёт разделяемую ссылку на элемент по наивной позиции.
+    ///
+    /// Возвращает `None`, если ячейка пуста или счётчик ссылок переполнен.
+    pub fn get_ref(&mut self, naive_pos: isize) -> Option<FrodoRef> {
+        let real_pos = self.ring.checked_real_pos(naive_pos)?;
+        if !self.ring.occupied[real_pos] {
+            return None;
+        }
+        self.acquire(real_pos)
+    }
+
+    /// Создаёт ещё одну ссылку на тот же элемент.
+    ///
+    /// Возвращает `None`, если ссылка не относится к живому элементу этой очереди или счётчик переполнен.
+    pub fn clone_ref(&mut self, r: &FrodoRef) -> Option<FrodoRef> {
+        if !self.is_live(r) {
+            return None;
+        }
+        self.acquire(r.real_pos)
+    }
+
+    /// Получает элемент по ссылке.
+    ///
+    /// Возвращает `None`, если ссылка не относится к живому элементу этой очереди.
+    pub fn deref(&self, r: &FrodoRef) -> Option<&T> {
+        if !self.is_live(r) {
+            return None;
+        }
+        Some(unsafe { self.ring.buffer[r.real_pos].assume_init_ref() })
+    }
+
+    /// Возвращает ссылку; когда ссылок на ячейку не остаётся, её элемент снова можно изъять.
+    ///
+    /// Возвращает `false`, если ссылка не относится к живому элементу этой очереди; счётчики тогда не меняются.
+    pub fn release(&mut self, r: FrodoRef) -> bool {
+        if !self.is_live(&r) {
+            return false;
+        }
+        self.refs[r.real_pos] -= 1;
+        self.ring.pins -= 1;
+        true
+    }
+
+    /// Проверяет, что ссылка указывает на занятую ячейку этой очереди, на которую есть ссылки.
+    fn is_live(&self, r: &FrodoRef) -> bool {
+        r.ring == self.id && self.ring.occupied[r.real_pos] && self.refs[r.real_pos] > 0
+    }
+
+    fn acquire(&mut self, real_pos: usize) -> Option<FrodoRef> {
+        self.refs[real_pos] = self.refs[real_pos].checked_add(1)?;
+        self.ring.pins += 1;
+        Some(FrodoRef {
+            ring: self.id,
+            real_pos,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rc_ring() {
+        let mut ring = FrodoRcRing::<u8, 3>::new();
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+
+        let a = ring.get_ref(1).unwrap();
+        let b = ring.clone_ref(&a).unwrap();
+        assert_eq!(ring.ref_count(1), 2);
+        assert_eq!(ring.deref(&b), Some(&0x2));
+
+        assert_eq!(ring.remove_at(1), None);
+        assert_eq!(ring.remove_at(2), Some(0x3));
+        assert_eq!(ring.pick(), Some(0x1));
+        assert_eq!(ring.pick(), None);
+
+        assert!(ring.push(0x4).is_ok());
+        assert!(ring.push(0x5).is_ok());
+        assert_eq!(ring.push(0x6), Err(0x6));
+
+        assert!(ring.release(a));
+        assert_eq!(ring.pick(), None);
+        assert!(ring.release(b));
+        assert_eq!(ring.pick(), Some(0x2));
+        assert_eq!(ring.pick(), Some(0x4));
+        assert_eq!(ring.pick(), Some(0x5));
+    }
+
+    #[test]
+    fn foreign_ref() {
+        let mut other = FrodoRcRing::<u8, 8>::new();
+        for i in 0..6 {
+            assert!(other.push(i).is_ok());
+        }
+        let foreign = other.get_ref(5).unwrap();
+
+        let mut ring = FrodoRcRing::<u8, 3>::new();
+        assert!(ring.push(0x1).is_ok());
+        assert_eq!(ring.deref(&foreign), None);
+        assert_eq!(ring.clone_ref(&foreign), None);
+        assert!(!ring.release(foreign));
+        assert_eq!(ring.pick(), Some(0x1));
+
+        let foreign = other.get_ref(0).unwrap();
+        assert!(ring.push(0x2).is_ok());
+        let own = ring.get_ref(0).unwrap();
+        assert_eq!(ring.deref(&foreign), None);
+        assert_eq!(ring.clone_ref(&foreign), None);
+        assert!(!ring.release(foreign));
+        assert_eq!(ring.ref_count(0), 1);
+        assert_eq!(ring.pick(), None);
+        assert!(ring.release(own));
+        assert_eq!(ring.pick(), Some(0x2));
+    }
+}
@@ -0,0 +1,366 @@
+//! Очередь с читателями по эпохам: чтение без блокировок параллельно с добавлением элементов.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Очередь с одним писателем и до `R` одновременных читателей, итерирующих её без блокировок.
+///
+/// Читатель закрепляет текущую эпоху и видит очередь такой, какой она была в этот момент: элементы,
+/// изъятые позже, остаются ему видны. Писатель изымает элементы, помечая их эпохой изъятия (`retire_at()`),
+/// а освобождает (вызывает `drop`) лишь тогда, когда ни один читатель не держит более раннюю эпоху.
+/// Как и в `FrodoRing`, изъятые из середины элементы оставляют пропуски, которые освобождаются,
+/// когда начало очереди доходит до них; элементы никогда не перемещаются.
+///
+/// Номера элементов свободно переполняются, поэтому ёмкость `N` должна быть степенью двойки;
+/// другие значения отвергаются при компиляции.
+pub struct FrodoRcuRing<T, const N: usize, const R: usize> {
+    buffer: UnsafeCell<[MaybeUninit<T>; N]>,
+    /// Логический номер элемента в каждой ячейке.
+    seq: [AtomicUsize; N],
+    /// Эпоха изъятия элемента в каждой ячейке; `0`, если элемент не изъят.
+    retired: [AtomicUsize; N],
+    /// Закреплённые читателями эпохи; `0` - свободный слот.
+    readers: [AtomicUsize; R],
+    /// Текущая эпоха, начиная с `1`; переполняется через ноль, минуя его.
+    epoch: AtomicUsize,
+    /// Номер первого неосвобождённого элемента.
+    head: AtomicUsize,
+    /// Число положенных элементов.
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send + Sync, const N: usize, const R: usize> Sync for FrodoRcuRing<T, N, R> {}
+
+impl<T, const N: usize, const R: usize> Default for FrodoRcuRing<T, N, R> {
+    fn default() -> Self {
+        let () = Self::POWER_OF_TWO;
+
+        Self {
+            buffer: UnsafeCell::new([const { MaybeUninit::uninit() }; N]),
+            seq: [const { AtomicUsize::new(0) }; N],
+            retired: [const { AtomicUsize::new(0) }; N],
+            readers: [const { AtomicUsize::new(0) }; R],
+            epoch: AtomicUsize::new(1),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T, const N: usize, const R: usize> Drop for FrodoRcuRing<T, N, R> {
+    fn drop(&mut self) {
+        let (head, tail) = (*self.head.get_mut(), *self.tail.get_mut());
+        for offset in 0..tail.wrapping_sub(head) {
+            let idx = head.wrapping_add(offset);
+            unsafe { self.buffer.get_mut()[idx % N].assume_init_drop() };
+        }
+    }
+}
+
+impl<T, const N: usize, const R: usize> FrodoRcuRing<T, N, R> {
+    /// Ёмкость, не являющаяся степенью двойки (в том числе нулевая), отвергается при компиляции.
+    const POWER_OF_TWO: () = assert!(
+        N.is_power_of_two(),
+        "FrodoRcuRing capacity N must be a power of two"
+    );
+
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Разделяет очередь на единственного писателя и копируемого читателя.
+    pub fn split(&mut self) -> (FrodoRcuWriter<'_, T, N, R>, FrodoRcuReader<'_, T, N, R>) {
+        (FrodoRcuWriter { ring: self }, FrodoRcuReader { ring: self })
+    }
+
+    /// Сообщает, виден ли элемент `idx` читателю, закрепившему эпоху `epoch`.
+    fn visible(&self, idx: usize, epoch: usize) -> bool {
+        // Сначала эпоха изъятия, затем номер: при повторном использовании ячейки номер записывается раньше
+        let retired = self.retired[idx % N].load(Ordering::SeqCst);
+        (retired == 0 || epoch_at_least(retired, epoch))
+            && self.seq[idx % N].load(Ordering::Acquire) == idx
+    }
+}
+
+/// Сравнивает эпохи с учётом переполнения: эпоха `a` не раньше `b`.
+fn epoch_at_least(a: usize, b: usize) -> bool {
+    a.wrapping_sub(b) as isize >= 0
+}
+
+/// Сторона писателя `FrodoRcuRing`.
+pub struct FrodoRcuWriter<'ring, T, const N: usize, const R: usize> {
+    ring: &'ring FrodoRcuRing<T, N, R>,
+}
+
+unsafe impl<T: Send + Sync, const N: usize, const R: usize> Send for FrodoRcuWriter<'_, T, N, R> {}
+
+impl<T, const N: usize, const R: usize> FrodoRcuWriter<'_, T, N, R> {
+    /// Возвращает использованное число ячеек, включая изъятые, но ещё не освобождённые элементы.
+    pub fn used(&self) -> usize {
+        self.ring
+            .tail
+            .load(Ordering::Relaxed)
+            .wrapping_sub(self.ring.head.load(Ordering::Relaxed))
+    }
+
+    /// Кладёт элемент в очередь, при необходимости освобождая изъятые элементы в её начале.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.used() == N {
+            self.reclaim();
+            if self.used() == N {
+                return Err(item);
+            }
+        }
+
+        let ring = self.ring;
+        let tail = ring.tail.load(Ordering::Relaxed);
+        unsafe { (*ring.buffer.get())[tail % N].write(item) };
+        ring.seq[tail % N].store(tail, Ordering::Release);
+        ring.retired[tail % N].store(0, Ordering::SeqCst);
+        ring.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Изымает элемент по наивной позиции. Возвращает `false`, если ячейка пуста или уже изъята.
+    ///
+    /// Элемент остаётся доступен читателям, закрепившим эпоху раньше, и освобождается позднее.
+    pub fn retire_at(&mut self, naive_pos: usize) -> bool {
+        let ring = self.ring;
+        if naive_pos >= self.used() {
+            return false;
+        }
+
+        let idx = ring.head.load(Ordering::Relaxed).wrapping_add(naive_pos);
+        if ring.retired[idx % N].load(Ordering::Relaxed) != 0 {
+            return false;
+        }
+
+        // Эпоха переполняется через ноль, минуя его: `0` означает, что элемент не изъят
+        let epoch = ring.epoch.load(Ordering::Relaxed);
+        ring.retired[idx % N].store(epoch, Ordering::SeqCst);
+        let next = match epoch.wrapping_add(1) {
+            0 => 1,
+            next => next,
+        };
+        ring.epoch.store(next, Ordering::SeqCst);
+        self.reclaim();
+        true
+    }
+
+    /// Освобождает изъятые элементы в начале очереди, которые не видны ни одному читателю.
+    pub fn reclaim(&mut self) {
+        let ring = self.ring;
+        let current = ring.epoch.load(Ordering::SeqCst);
+        let oldest = ring
+            .readers
+            .iter()
+            .map(|reader| reader.load(Ordering::SeqCst))
+            .filter(|epoch| *epoch != 0)
+            .max_by_key(|epoch| current.wrapping_sub(*epoch));
+
+        let tail = ring.tail.load(Ordering::Relaxed);
+        let mut head = ring.head.load(Ordering::Relaxed);
+        while head != tail {
+            let retired = ring.retired[head % N].load(Ordering::Relaxed);
+            if retired == 0 || oldest.is_some_and(|oldest| epoch_at_least(retired, oldest)) {
+                break;
+            }
+
+            unsafe { (*ring.buffer.get())[head % N].assume_init_drop() };
+            head = head.wrapping_add(1);
+            ring.head.store(head, Ordering::Release);
+        }
+    }
+}
+
+/// Сторона читателей `FrodoRcuRing`; дёшево копируется.
+pub struct FrodoRcuReader<'ring, T, const N: usize, const R: usize> {
+    ring: &'ring FrodoRcuRing<T, N, R>,
+}
+
+impl<T, const N: usize, const R: usize> Clone for FrodoRcuReader<'_, T, N, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize, const R: usize> Copy for FrodoRcuReader<'_, T, N, R> {}
+
+unsafe impl<T: Send + Sync, const N: usize, const R: usize> Send for FrodoRcuReader<'_, T, N, R> {}
+
+impl<'ring, T, const N: usize, const R: usize> FrodoRcuReader<'ring, T, N, R> {
+    /// Закрепляет текущую эпоху и возвращает представление очереди на этот момент.
+    ///
+    /// Возвращает `None`, если все `R` слотов читателей заняты.
+    pub fn read(&self) -> Option<FrodoRcuView<'ring, T, N, R>> {
+        let ring = self.ring;
+        let mut epoch = ring.epoch.load(Ordering::SeqCst);
+        let slot = ring.readers.iter().position(|reader| {
+            reader
+                .compare_exchange(0, epoch, Ordering::SeqCst, Ordering::Relaxed)
+                .is_ok()
+        })?;
+
+        // Эпоха могла смениться до того, как писатель увидел слот: закрепляем заново, пока она не устоится
+        loop {
+            let current = ring.epoch.load(Ordering::SeqCst);
+            if current == epoch {
+                break;
+            }
+            epoch = current;
+            ring.readers[slot].store(epoch, Ordering::SeqCst);
+        }
+
+        Some(FrodoRcuView {
+            ring,
+            slot,
+            epoch,
+            head: ring.head.load(Ordering::Acquire),
+            tail: ring.tail.load(Ordering::Acquire),
+        })
+    }
+}
+
+/// Представление `FrodoRcuRing` на момент закреплённой эпохи; освобождает слот читателя при удалении.
+pub struct FrodoRcuView<'ring, T, const N: usize, const R: usize> {
+    ring: &'ring FrodoRcuRing<T, N, R>,
+    slot: usize,
+    epoch: usize,
+    head: usize,
+    tail: usize,
+}
+
+impl<T, const N: usize, const R: usize> Drop for FrodoRcuView<'_, T, N, R> {
+    fn drop(&mut self) {
+        self.ring.readers[self.slot].store(0, Ordering::SeqCst);
+    }
+}
+
+impl<T, const N: usize, const R: usize> FrodoRcuView<'_, T, N, R> {
+    /// Возвращает закреплённую эпоху.
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
+
+    /// Создаёт итератор по элементам, видимым в закреплённой эпохе.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.tail.wrapping_sub(self.head))
+            .map(|offset| self.head.wrapping_add(offset))
+            .filter(|idx| self.ring.visible(*idx, self.epoch))
+            .map(|idx| unsafe { (*self.ring.buffer.get())[idx % N].assume_init_ref() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rcu_view_outlives_retire() {
+        let mut ring = FrodoRcuRing::<u8, 4, 1>::new();
+        let (mut writer, reader) = ring.split();
+        for i in 1..=4 {
+            writer.push(i).unwrap();
+        }
+
+        let view = reader.read().unwrap();
+        assert!(reader.read().is_none());
+        assert!(writer.retire_at(0));
+        assert!(!writer.retire_at(0));
+        assert!(writer.retire_at(2));
+
+        // Читатель всё ещё видит изъятые элементы, поэтому место не освобождается
+        assert_eq!(writer.push(5), Err(5));
+        assert_eq!(view.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+        drop(view);
+
+        writer.push(5).unwrap();
+        let view = reader.read().unwrap();
+        assert_eq!(view.iter().copied().collect::<Vec<_>>(), [2, 4, 5]);
+    }
+
+    #[test]
+    fn rcu_counter_wrap() {
+        let mut ring = FrodoRcuRing::<u8, 4, 1>::new();
+        ring.head = AtomicUsize::new(usize::MAX - 1);
+        ring.tail = AtomicUsize::new(usize::MAX - 1);
+        let (mut writer, reader) = ring.split();
+        for i in 1..=4 {
+            writer.push(i).unwrap();
+        }
+
+        assert!(writer.retire_at(0));
+        assert!(writer.retire_at(2));
+        assert_eq!(writer.used(), 3);
+        writer.push(5).unwrap();
+
+        let view = reader.read().unwrap();
+        assert_eq!(view.iter().copied().collect::<Vec<_>>(), [2, 3, 5]);
+    }
+
+    #[test]
+    fn rcu_epoch_wrap() {
+        let mut ring = FrodoRcuRing::<u8, 4, 1>::new();
+        ring.epoch = AtomicUsize::new(usize::MAX - 1);
+        let (mut writer, reader) = ring.split();
+        for i in 1..=4 {
+            writer.push(i).unwrap();
+        }
+
+        let view = reader.read().unwrap();
+        assert_eq!(view.epoch(), usize::MAX - 1);
+        assert!(writer.retire_at(0));
+        assert!(writer.retire_at(1));
+        assert!(writer.retire_at(2));
+        assert_eq!(writer.ring.epoch.load(Ordering::Relaxed), 2);
+
+        // Эпохи изъятия `MAX - 1`, `MAX` и `1` не раньше эпохи читателя
+        assert_eq!(view.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(writer.used(), 4);
+        drop(view);
+
+        writer.reclaim();
+        assert_eq!(writer.used(), 1);
+        let view = reader.read().unwrap();
+        assert_eq!(view.epoch(), 2);
+        assert_eq!(view.iter().copied().collect::<Vec<_>>(), [4]);
+    }
+
+    #[test]
+    fn rcu_concurrent_readers() {
+        let mut ring = FrodoRcuRing::<(u64, u64), 16, 2>::new();
+        let (mut writer, reader) = ring.split();
+
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..5000u64 {
+                    while writer.push((i, i * 3)).is_err() {
+                        std::thread::yield_now();
+                    }
+                    if writer.used() > 8 {
+                        writer.retire_at(0);
+                        writer.retire_at(1);
+                    }
+                }
+            });
+
+            for _ in 0..2 {
+                s.spawn(move || {
+                    for _ in 0..2000 {
+                        let Some(view) = reader.read() else {
+                            continue;
+                        };
+                        let mut prev = None;
+                        for (i, check) in view.iter() {
+                            assert_eq!(*check, i * 3);
+                            assert!(prev.is_none_or(|prev| prev < *i));
+                            prev = Some(*i);
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
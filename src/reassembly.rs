@@ -0,0 +1,223 @@
+//! Сборка пакетов из потока байт в очереди.
+
+use crate::FrodoRing;
+
+/// Способ разделения потока байт на пакеты.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// Каждому пакету предшествует его длина: 2 байта, LE.
+    LengthPrefix,
+    /// Пакет завершается байтом-разделителем, который в пакет не входит; пустые пакеты пропускаются.
+    Delimiter(u8),
+    /// Пакет закодирован COBS и завершается нулевым байтом; выдаётся декодированным.
+    Cobs,
+}
+
+/// Конечный автомат, забирающий байты из `FrodoRing<u8, N>` и выдающий целые пакеты длиной до `M` байт.
+///
+/// Незавершённый пакет копится внутри между вызовами `poll()`, так что пакет может приходить частями,
+/// в том числе через перенос очереди. Слишком длинные и некорректные пакеты отбрасываются и учитываются в `errors()`.
+pub struct Reassembler<const M: usize> {
+    framing: Framing,
+    buffer: [u8; M],
+    len: usize,
+    /// Младший байт префикса длины, если он уже прочитан; префикс не занимает буфер пакета.
+    prefix_low: Option<u8>,
+    /// Длина пакета из префикса, если префикс уже прочитан.
+    expected: Option<usize>,
+    /// Число байт отбрасываемого пакета, которые ещё нужно пропустить (для `LengthPrefix`).
+    skip: usize,
+    /// Отбрасывается слишком длинный пакет до ближайшего разделителя.
+    discarding: bool,
+    /// В буфере лежит выданный пакет, который нужно сбросить при следующем вызове.
+    ready: bool,
+    errors: u32,
+}
+
+impl<const M: usize> Reassembler<M> {
+    /// Создаёт автомат с заданным способом разделения пакетов.
+    pub fn new(framing: Framing) -> Self {
+        Self {
+            framing,
+            buffer: [0; M],
+            len: 0,
+            prefix_low: None,
+            expected: None,
+            skip: 0,
+            discarding: false,
+            ready: false,
+            errors: 0,
+        }
+    }
+
+    /// Возвращает число отброшенных пакетов (с переполнением через ноль).
+    pub fn errors(&self) -> u32 {
+        self.errors
+    }
+
+    /// Забирает байты из очереди до конца ближайшего пакета и выдаёт его.
+    ///
+    /// Возвращает `None`, если байты в очереди кончились раньше; собранная часть пакета сохраняется.
+    pub fn poll<const N: usize>(&mut self, ring: &mut FrodoRing<u8, N>) -> Option<&[u8]> {
+        if self.ready {
+            self.ready = false;
+            self.len = 0;
+        }
+
+        while let Some(byte) = ring.pick() {
+            let complete = match self.framing {
+                Framing::LengthPrefix => self.length_prefixed(byte),
+                Framing::Delimiter(delimiter) => self.delimited(byte, delimiter),
+                Framing::Cobs => self.delimited(byte, 0) && self.decode_cobs(),
+            };
+
+            if complete {
+                self.ready = true;
+                return Some(&self.buffer[..self.len]);
+            }
+        }
+
+        None
+    }
+
+    fn reject(&mut self) {
+        self.errors = self.errors.wrapping_add(1);
+        self.len = 0;
+    }
+
+    fn length_prefixed(&mut self, byte: u8) -> bool {
+        if self.skip > 0 {
+            self.skip -= 1;
+            return false;
+        }
+
+        let Some(expected) = self.expected else {
+            let Some(low) = self.prefix_low.take() else {
+                self.prefix_low = Some(byte);
+                return false;
+            };
+
+            let expected = u16::from_le_bytes([low, byte]) as usize;
+            if expected > M {
+                self.reject();
+                self.skip = expected;
+                return false;
+            }
+            if expected == 0 {
+                return true;
+            }
+            self.expected = Some(expected);
+            return false;
+        };
+
+        self.buffer[self.len] = byte;
+        self.len += 1;
+        if self.len < expected {
+            return false;
+        }
+        self.expected = None;
+        true
+    }
+
+    fn delimited(&mut self, byte: u8, delimiter: u8) -> bool {
+        if byte == delimiter {
+            let complete = !self.discarding && self.len > 0;
+            self.discarding = false;
+            return complete;
+        }
+
+        if self.discarding {
+            return false;
+        }
+        if self.len == M {
+            self.reject();
+            self.discarding = true;
+            return false;
+        }
+
+        self.buffer[self.len] = byte;
+        self.len += 1;
+        false
+    }
+
+    /// Декодирует COBS на месте; некорректный пакет отбрасывает.
+    fn decode_cobs(&mut self) -> bool {
+        let mut read = 0;
+        let mut write = 0;
+
+        while read < self.len {
+            let code = self.buffer[read] as usize;
+            if code == 0 || read + code > self.len {
+                self.reject();
+                return false;
+            }
+
+            read += 1;
+            self.buffer.copy_within(read..read + code - 1, write);
+            read += code - 1;
+            write += code - 1;
+            if code != 0xFF && read < self.len {
+                self.buffer[write] = 0;
+                write += 1;
+            }
+        }
+
+        self.len = write;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed<const N: usize>(ring: &mut FrodoRing<u8, N>, bytes: &[u8]) {
+        for byte in bytes {
+            assert!(ring.push(*byte).is_ok());
+        }
+    }
+
+    #[test]
+    fn reassembler() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+
+        let mut cobs = Reassembler::<8>::new(Framing::Cobs);
+        feed(&mut ring, &[0x03, 0x11, 0x22]);
+        assert_eq!(cobs.poll(&mut ring), None);
+        feed(&mut ring, &[0x02, 0x33, 0x00, 0x01, 0x00]);
+        assert_eq!(cobs.poll(&mut ring), Some(&[0x11, 0x22, 0x00, 0x33][..]));
+        assert_eq!(cobs.poll(&mut ring), Some(&[][..]));
+
+        let mut prefixed = Reassembler::<4>::new(Framing::LengthPrefix);
+        feed(&mut ring, &[0x06, 0x00, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(prefixed.poll(&mut ring), None);
+        assert_eq!(prefixed.errors(), 1);
+        feed(&mut ring, &[0x02, 0x00, 0xAA, 0xBB]);
+        assert_eq!(prefixed.poll(&mut ring), Some(&[0xAA, 0xBB][..]));
+
+        let mut lines = Reassembler::<4>::new(Framing::Delimiter(b'\n'));
+        feed(&mut ring, b"toolong\n");
+        assert_eq!(lines.poll(&mut ring), None);
+        feed(&mut ring, b"\nok\n");
+        assert_eq!(lines.poll(&mut ring), Some(&b"ok"[..]));
+        assert_eq!(lines.errors(), 1);
+    }
+
+    #[test]
+    fn length_prefix_small_buffer() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+
+        let mut tiny = Reassembler::<1>::new(Framing::LengthPrefix);
+        feed(&mut ring, &[0x01, 0x00, 0x7F, 0x00, 0x00, 0x02, 0x00, 0xAA]);
+        assert_eq!(tiny.poll(&mut ring), Some(&[0x7F][..]));
+        assert_eq!(tiny.poll(&mut ring), Some(&[][..]));
+        assert_eq!(tiny.poll(&mut ring), None);
+        assert_eq!(tiny.errors(), 1);
+
+        let mut empty = Reassembler::<0>::new(Framing::LengthPrefix);
+        feed(&mut ring, &[0x01, 0x00, 0xBB, 0x00, 0x00]);
+        assert_eq!(empty.poll(&mut ring), Some(&[][..]));
+        assert_eq!(empty.errors(), 1);
+        assert!(ring.is_empty());
+    }
+}
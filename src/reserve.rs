@@ -0,0 +1,172 @@
+//! Двухфазное добавление: резервирование ячейки и последующее подтверждение или отмена.
+
+use core::mem::MaybeUninit;
+
+use crate::FrodoRing;
+
+/// Зарезервированная ячейка очереди, выданная `reserve()`.
+///
+/// Ячейка занимает место в очереди в порядке резервирования, но не видна потребителям до `commit_slot()`.
+/// Не копируется: каждое резервирование завершается ровно один раз.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FrodoSlot {
+    real_pos: usize,
+}
+
+impl FrodoSlot {
+    /// Возвращает реальную позицию зарезервированной ячейки в буфере.
+    pub fn real_pos(&self) -> usize {
+        self.real_pos
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Резервирует ячейку в конце очереди, чтобы заполнить её позже (например, по завершении DMA).
+    ///
+    /// Пока есть незавершённые резервирования, очередь не проводит сжатие: зарезервированные ячейки не перемещаются.
+    /// Возвращает `None`, если свободной ячейки нет или открыта транзакция.
+    pub fn reserve(&mut self) -> Option<FrodoSlot> {
+        if self.staging.is_some() {
+            return None;
+        }
+
+        let real_pos = self.free_cell()?;
+        self.reserved[real_pos] = true;
+        self.reservations += 1;
+        self.cap += 1;
+        Some(FrodoSlot { real_pos })
+    }
+
+    /// Возвращает число незавершённых резервирований.
+    pub fn reservations(&self) -> usize {
+        self.reservations
+    }
+
+    /// Получает зарезервированную ячейку для заполнения.
+    ///
+    /// Возвращает `None`, если резервирование уже завершено или выдано другой очередью.
+    pub fn slot_mut(&mut self, slot: &FrodoSlot) -> Option<&mut MaybeUninit<T>> {
+        if !self.is_reserved(slot) {
+            return None;
+        }
+        Some(&mut self.buffer[slot.real_pos])
+    }
+
+    /// Делает заполненную зарезервированную ячейку видимой в очереди.
+    ///
+    /// Возвращает `false`, если резервирование уже было отменено (например, через `clear()`).
+    ///
+    /// # Safety
+    ///
+    /// Ячейка должна быть полностью инициализирована через `slot_mut()`.
+    pub unsafe fn commit_slot(&mut self, slot: FrodoSlot) -> bool {
+        if !self.is_reserved(&slot) {
+            return false;
+        }
+
+        self.reserved[slot.real_pos] = false;
+        self.occupied[slot.real_pos] = true;
+        self.reservations -= 1;
+        #[cfg(feature = "journal")]
+        self.record(
+            crate::journal::JournalOp::Push,
+            (slot.real_pos + N - self.head) % N,
+        );
+        self.check_watermarks();
+        self.note_depth();
+        #[cfg(feature = "stats")]
+        self.sample_depth();
+        true
+    }
+
+    /// Отменяет резервирование, освобождая ячейку.
+    ///
+    /// Содержимое ячейки не удаляется: если она была инициализирована, значение будет утеряно без вызова `drop`.
+    pub fn abort_slot(&mut self, slot: FrodoSlot) {
+        if self.is_reserved(&slot) {
+            self.reserved[slot.real_pos] = false;
+            self.reservations -= 1;
+            self.trim();
+        }
+    }
+
+    fn is_reserved(&self, slot: &FrodoSlot) -> bool {
+        slot.real_pos < N && self.reserved[slot.real_pos]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserve() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        let slot = ring.reserve().unwrap();
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.reservations(), 1);
+        assert_eq!(ring.used(), 3);
+        assert_eq!(ring.len(), 2);
+
+        assert_eq!(ring.pick(), Some(0x1));
+        assert_eq!(ring.at(0), None);
+        assert!(!ring.is_empty());
+        assert_eq!(ring.pick(), Some(0x3));
+        assert!(ring.is_empty());
+        assert_eq!(ring.used(), 1);
+
+        ring.slot_mut(&slot).unwrap().write(0x2);
+        assert!(unsafe { ring.commit_slot(slot) });
+        assert_eq!(ring.reservations(), 0);
+        assert_eq!(ring.pick(), Some(0x2));
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn reserve_abort() {
+        let mut ring = FrodoRing::<u8, 3>::new();
+
+        assert!(ring.push(0x1).is_ok());
+        let a = ring.reserve().unwrap();
+        let b = ring.reserve().unwrap();
+        assert_eq!(ring.push(0x4), Err(0x4));
+
+        assert_eq!(ring.pick(), Some(0x1));
+        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.push(0x5), Err(0x5));
+
+        ring.abort_slot(b);
+        assert_eq!(ring.used(), 3);
+        ring.abort_slot(a);
+        assert_eq!(ring.used(), 1);
+        assert_eq!(ring.reservations(), 0);
+
+        assert!(ring.push(0x5).is_ok());
+        assert_eq!(ring.pick(), Some(0x4));
+        assert_eq!(ring.pick(), Some(0x5));
+    }
+
+    #[test]
+    fn stale_slot() {
+        let mut other = FrodoRing::<u8, 8>::new();
+        for i in 0..6 {
+            assert!(other.push(i).is_ok());
+        }
+        let foreign = other.reserve().unwrap();
+
+        let mut ring = FrodoRing::<u8, 4>::new();
+        let slot = ring.reserve().unwrap();
+        assert!(ring.slot_mut(&foreign).is_none());
+        assert!(!unsafe { ring.commit_slot(foreign) });
+
+        let stale = FrodoSlot {
+            real_pos: slot.real_pos(),
+        };
+        ring.abort_slot(slot);
+        assert!(ring.slot_mut(&stale).is_none());
+        ring.abort_slot(stale);
+        assert_eq!(ring.reservations(), 0);
+    }
+}
@@ -0,0 +1,151 @@
+//! Пул кольцевых очередей, выдаваемых и возвращаемых целиком.
+
+use crate::FrodoRing;
+
+/// Дескриптор очереди, выданной пулом.
+///
+/// Не копируется: после `release()` обратиться к очереди через него уже нельзя.
+///
+/// Дескриптор помечен идентификатором выдавшего его пула: методы другого пула возвращают для него `None`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FrodoRingHandle {
+    pool: usize,
+    index: usize,
+}
+
+impl FrodoRingHandle {
+    /// Возвращает номер очереди в пуле.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Пул из `K` очередей на `N` элементов каждая, размещённых в одном статическом блоке памяти.
+///
+/// Подходит для протоколов, которым нужна отдельная очередь на каждое соединение, но нельзя аллоцировать.
+pub struct FrodoRingPool<T, const N: usize, const K: usize> {
+    rings: [FrodoRing<T, N>; K],
+    in_use: [bool; K],
+    /// Идентификатор пула, которым помечаются его дескрипторы.
+    id: usize,
+}
+
+impl<T, const N: usize, const K: usize> Default for FrodoRingPool<T, N, K> {
+    fn default() -> Self {
+        Self {
+            rings: core::array::from_fn(|_| FrodoRing::new()),
+            in_use: [false; K],
+            id: crate::next_instance_id(),
+        }
+    }
+}
+
+impl<T, const N: usize, const K: usize> FrodoRingPool<T, N, K> {
+    /// Создаёт новый пул.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает число выданных очередей.
+    pub fn in_use(&self) -> usize {
+        self.in_use.iter().filter(|v| **v).count()
+    }
+
+    /// Возвращает число свободных очередей.
+    pub fn available(&self) -> usize {
+        K - self.in_use()
+    }
+
+    /// Выдаёт свободную (пустую) очередь из пула.
+    pub fn acquire(&mut self) -> Option<FrodoRingHandle> {
+        let index = self.in_use.iter().position(|v| !*v)?;
+        self.in_use[index] = true;
+        Some(FrodoRingHandle {
+            pool: self.id,
+            index,
+        })
+    }
+
+    /// Возвращает очередь в пул, удаляя все её элементы.
+    ///
+    /// Возвращает `false`, если дескриптор не относится к выданной очереди этого пула.
+    pub fn release(&mut self, handle: FrodoRingHandle) -> bool {
+        if !self.is_issued(&handle) {
+            return false;
+        }
+
+        self.rings[handle.index].clear();
+        self.in_use[handle.index] = false;
+        true
+    }
+
+    /// Получает выданную очередь; `None`, если дескриптор не относится к выданной очереди этого пула.
+    pub fn ring(&self, handle: &FrodoRingHandle) -> Option<&FrodoRing<T, N>> {
+        self.is_issued(handle).then(|| &self.rings[handle.index])
+    }
+
+    /// Получает выданную очередь для изменения; `None`, если дескриптор не относится к выданной очереди этого пула.
+    pub fn ring_mut(&mut self, handle: &FrodoRingHandle) -> Option<&mut FrodoRing<T, N>> {
+        if !self.is_issued(handle) {
+            return None;
+        }
+        Some(&mut self.rings[handle.index])
+    }
+
+    fn is_issued(&self, handle: &FrodoRingHandle) -> bool {
+        handle.pool == self.id && self.in_use[handle.index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_pool() {
+        let mut pool = FrodoRingPool::<u8, 4, 2>::new();
+        assert_eq!(pool.available(), 2);
+
+        let a = pool.acquire().unwrap();
+        let b = pool.acquire().unwrap();
+        assert!(pool.acquire().is_none());
+        assert_eq!(pool.in_use(), 2);
+
+        assert!(pool.ring_mut(&a).unwrap().push(0x1).is_ok());
+        assert!(pool.ring_mut(&b).unwrap().push(0x2).is_ok());
+        assert_eq!(pool.ring(&a).unwrap().at(0), Some(&0x1));
+        assert_eq!(pool.ring(&b).unwrap().at(0), Some(&0x2));
+
+        let index = a.index();
+        assert!(pool.release(a));
+        assert_eq!(pool.available(), 1);
+
+        let c = pool.acquire().unwrap();
+        assert_eq!(c.index(), index);
+        assert!(pool.ring(&c).unwrap().is_empty());
+    }
+
+    #[test]
+    fn foreign_handle() {
+        let mut other = FrodoRingPool::<u8, 4, 4>::new();
+        let handles = [(); 4].map(|()| other.acquire().unwrap());
+        let [.., foreign] = handles;
+
+        let mut pool = FrodoRingPool::<u8, 4, 2>::new();
+        assert!(pool.ring(&foreign).is_none());
+        assert!(pool.ring_mut(&foreign).is_none());
+        assert!(!pool.release(foreign));
+
+        let mut other = FrodoRingPool::<u8, 4, 2>::new();
+        let same_index = other.acquire().unwrap();
+        assert!(pool.ring(&same_index).is_none());
+        assert_eq!(pool.available(), 2);
+
+        let own = pool.acquire().unwrap();
+        assert!(pool.ring_mut(&same_index).is_none());
+        assert!(!pool.release(same_index));
+        assert_eq!(own.index(), 0);
+        assert!(pool.ring(&own).is_some());
+        assert_eq!(pool.in_use(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+//! Разделение ролей производителя и потребителя на уровне типов.
+
+use core::marker::PhantomData;
+
+use crate::{FrodoRing, FrodoRingIterator};
+
+/// Очередь, класть в которую можно только через `ProducerToken`, а изымать - только через `ConsumerToken`.
+///
+/// Сама очередь не атомарна и разделяется обычным способом (например, под критической секцией),
+/// а токены закрепляют роли: обработчик прерывания, владеющий лишь `ProducerToken`,
+/// не сможет ничего изъять, а задача с `ConsumerToken` - ничего положить. Чтение доступно всем.
+pub struct FrodoRoleRing<T, const N: usize> {
+    ring: FrodoRing<T, N>,
+    split: bool,
+    /// Идентификатор очереди, которым помечаются её токены.
+    id: usize,
+}
+
+/// Право класть элементы в `FrodoRoleRing`.
+///
+/// Токен не заимствует очередь, поэтому его можно хранить, например, в статической переменной рядом с очередью.
+/// Он помечен идентификатором выдавшей его очереди и к другой очереди не подходит.
+pub struct ProducerToken<T, const N: usize> {
+    ring: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Право изымать элементы из `FrodoRoleRing`.
+///
+/// Как и `ProducerToken`, хранится отдельно от очереди и подходит только к выдавшей его очереди.
+pub struct ConsumerToken<T, const N: usize> {
+    ring: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, const N: usize> Default for FrodoRoleRing<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            split: false,
+            id: crate::next_instance_id(),
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoRoleRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Выдаёт токены ролей; возвращает `None`, если они уже были выданы.
+    ///
+    /// Токены можно разнести по статическим переменным, например, отдав `ProducerToken` обработчику прерывания:
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    ///
+    /// use frodo_ring::{ConsumerToken, FrodoRoleRing, ProducerToken};
+    ///
+    /// static RING: Mutex<Option<FrodoRoleRing<u8, 4>>> = Mutex::new(None);
+    /// static PRODUCER: Mutex<Option<ProducerToken<u8, 4>>> = Mutex::new(None);
+    ///
+    /// fn isr() {
+    ///     let mut ring = RING.lock().unwrap();
+    ///     let mut producer = PRODUCER.lock().unwrap();
+    ///     let _ = producer.as_mut().unwrap().push(ring.as_mut().unwrap(), 0x1);
+    /// }
+    ///
+    /// let mut ring = FrodoRoleRing::new();
+    /// let (producer, mut consumer): (_, ConsumerToken<u8, 4>) = ring.split_roles().unwrap();
+    /// *RING.lock().unwrap() = Some(ring);
+    /// *PRODUCER.lock().unwrap() = Some(producer);
+    ///
+    /// isr();
+    /// assert_eq!(consumer.pick(RING.lock().unwrap().as_mut().unwrap()), Some(0x1));
+    /// ```
+    pub fn split_roles(&mut self) -> Option<(ProducerToken<T, N>, ConsumerToken<T, N>)> {
+        if self.split {
+            return None;
+        }
+
+        self.split = true;
+        Some((
+            ProducerToken {
+                ring: self.id,
+                _marker: PhantomData,
+            },
+            ConsumerToken {
+                ring: self.id,
+                _marker: PhantomData,
+            },
+        ))
+    }
+
+    /// Возвращает использованное число ячеек кольцевой очереди.
+    pub fn used(&self) -> usize {
+        self.ring.used()
+    }
+
+    /// Возвращает число элементов, находящихся в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Получает элемент по ячейке (наивной позиции); см. `FrodoRing::at`.
+    pub fn at(&self, naive_pos: isize) -> Option<&T> {
+        self.ring.at(naive_pos)
+    }
+
+    /// Создаёт итератор по очереди.
+    pub fn iter(&self) -> FrodoRingIterator<'_, T, N> {
+        self.ring.iter()
+    }
+}
+
+impl<T, const N: usize> ProducerToken<T, N> {
+    /// Кладёт элемент в очередь; см. `FrodoRing::push`.
+    ///
+    /// Возвращает элемент обратно, если токен выдан другой очередью.
+    pub fn push(&mut self, ring: &mut FrodoRoleRing<T, N>, item: T) -> Result<(), T> {
+        if ring.id != self.ring {
+            return Err(item);
+        }
+        ring.ring.push(item)
+    }
+}
+
+impl<T, const N: usize> ConsumerToken<T, N> {
+    /// Изымает первый элемент очереди; см. `FrodoRing::pick`.
+    ///
+    /// Возвращает `None`, если токен выдан другой очередью.
+    pub fn pick(&mut self, ring: &mut FrodoRoleRing<T, N>) -> Option<T> {
+        if ring.id != self.ring {
+            return None;
+        }
+        ring.ring.pick()
+    }
+
+    /// Изымает элемент по наивной позиции; см. `FrodoRing::remove_at`.
+    ///
+    /// Возвращает `None`, если токен выдан другой очередью.
+    pub fn remove_at(&mut self, ring: &mut FrodoRoleRing<T, N>, naive_pos: isize) -> Option<T> {
+        if ring.id != self.ring {
+            return None;
+        }
+        ring.ring.remove_at(naive_pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_roles() {
+        let mut ring = FrodoRoleRing::<u8, 4>::new();
+        let (mut producer, mut consumer) = ring.split_roles().unwrap();
+        assert!(ring.split_roles().is_none());
+
+        producer.push(&mut ring, 1).unwrap();
+        producer.push(&mut ring, 2).unwrap();
+        assert_eq!(ring.at(1), Some(&2));
+        assert_eq!(consumer.remove_at(&mut ring, 1), Some(2));
+        assert_eq!(consumer.pick(&mut ring), Some(1));
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn foreign_tokens() {
+        let mut a = FrodoRoleRing::<u8, 4>::new();
+        let mut b = FrodoRoleRing::<u8, 4>::new();
+        let (mut producer, _) = a.split_roles().unwrap();
+        let (mut other, mut consumer) = b.split_roles().unwrap();
+
+        assert_eq!(producer.push(&mut b, 1), Err(1));
+        assert!(b.is_empty());
+
+        other.push(&mut b, 2).unwrap();
+        producer.push(&mut a, 3).unwrap();
+        assert_eq!(consumer.pick(&mut a), None);
+        assert_eq!(consumer.remove_at(&mut a, 0), None);
+        assert_eq!(a.len(), 1);
+        assert_eq!(consumer.pick(&mut b), Some(2));
+    }
+}
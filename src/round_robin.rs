@@ -0,0 +1,131 @@
+//! Планировщик, обслуживающий несколько логических очередей по кругу.
+
+use crate::FrodoRing;
+
+/// Набор из `Q` логических очередей, разделяющих один кольцевой буфер на `N` ячеек.
+///
+/// `pick()` обходит очереди по кругу, поэтому ни один источник не может монополизировать обработку;
+/// порядок FIFO внутри каждой из очередей при этом сохраняется.
+pub struct FrodoRoundRobin<T, const N: usize, const Q: usize> {
+    /// Общий буфер; каждый элемент помечен номером своей очереди.
+    ring: FrodoRing<(usize, T), N>,
+    /// Число элементов в каждой из очередей - позволяет не искать элементы в пустых очередях.
+    lens: [usize; Q],
+    /// Очередь, с которой начнётся следующий `pick()`.
+    next: usize,
+}
+
+impl<T, const N: usize, const Q: usize> Default for FrodoRoundRobin<T, N, Q> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            lens: [0; Q],
+            next: 0,
+        }
+    }
+}
+
+impl<T, const N: usize, const Q: usize> FrodoRoundRobin<T, N, Q> {
+    /// Создаёт новый планировщик.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает общее число элементов во всех очередях.
+    pub fn len(&self) -> usize {
+        self.lens.iter().sum()
+    }
+
+    /// Возвращает число элементов в очереди `queue`.
+    pub fn len_of(&self, queue: usize) -> usize {
+        self.lens.get(queue).copied().unwrap_or(0)
+    }
+
+    /// Сообщает, есть ли элементы хотя бы в одной из очередей.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Кладёт элемент в очередь `queue`.
+    ///
+    /// Возвращает элемент обратно, если такой очереди нет или общий буфер заполнен.
+    pub fn push(&mut self, queue: usize, item: T) -> Result<(), T> {
+        if queue >= Q {
+            return Err(item);
+        }
+
+        self.ring.push((queue, item)).map_err(|(_, item)| item)?;
+        self.lens[queue] += 1;
+        Ok(())
+    }
+
+    /// Отдаёт первый элемент очереди `queue`, изымая его.
+    pub fn pick_from(&mut self, queue: usize) -> Option<T> {
+        if self.len_of(queue) == 0 {
+            return None;
+        }
+
+        let naive_pos = (0..self.ring.used())
+            .find(|i| matches!(self.ring.at(*i as isize), Some((q, _)) if *q == queue))?;
+        let (_, item) = self.ring.remove_at(naive_pos as isize)?;
+        self.lens[queue] -= 1;
+        Some(item)
+    }
+
+    /// Отдаёт элемент из следующей по кругу непустой очереди.
+    pub fn pick(&mut self) -> Option<T> {
+        for i in 0..Q {
+            let queue = (self.next + i) % Q;
+            if let Some(item) = self.pick_from(queue) {
+                self.next = (queue + 1) % Q;
+                return Some(item);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_robin() {
+        let mut rr = FrodoRoundRobin::<u8, 8, 3>::new();
+
+        assert!(rr.push(0, 0x01).is_ok());
+        assert!(rr.push(0, 0x02).is_ok());
+        assert!(rr.push(0, 0x03).is_ok());
+        assert!(rr.push(2, 0x21).is_ok());
+        assert!(rr.push(2, 0x22).is_ok());
+        assert!(rr.push(3, 0x31).is_err());
+
+        assert_eq!(rr.len(), 5);
+        assert_eq!(rr.len_of(1), 0);
+
+        assert_eq!(rr.pick(), Some(0x01));
+        assert_eq!(rr.pick(), Some(0x21));
+        assert!(rr.push(1, 0x11).is_ok());
+        assert_eq!(rr.pick(), Some(0x02));
+        assert_eq!(rr.pick(), Some(0x11));
+        assert_eq!(rr.pick(), Some(0x22));
+        assert_eq!(rr.pick(), Some(0x03));
+        assert_eq!(rr.pick(), None);
+        assert!(rr.is_empty());
+    }
+
+    #[test]
+    fn round_robin_full() {
+        let mut rr = FrodoRoundRobin::<u8, 2, 2>::new();
+
+        assert!(rr.push(0, 0x01).is_ok());
+        assert!(rr.push(1, 0x11).is_ok());
+        assert_eq!(rr.push(1, 0x12), Err(0x12));
+
+        assert_eq!(rr.pick_from(1), Some(0x11));
+        assert!(rr.push(1, 0x12).is_ok());
+        assert_eq!(rr.pick(), Some(0x01));
+        assert_eq!(rr.pick(), Some(0x12));
+    }
+}
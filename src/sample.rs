@@ -0,0 +1,173 @@
+//! Политики переполнения, сохраняющие представительную выборку за весь период.
+
+use crate::FrodoRing;
+use crate::random::random_below_u64;
+
+/// Очередь, которая после заполнения хранит равномерную случайную выборку всех предложенных элементов
+/// (reservoir sampling, алгоритм R), сохраняя их хронологический порядок.
+pub struct FrodoReservoir<T, const N: usize> {
+    ring: FrodoRing<T, N>,
+    seen: u64,
+}
+
+impl<T, const N: usize> Default for FrodoReservoir<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            seen: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoReservoir<T, N> {
+    /// Создаёт новую выборку.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает очередь с текущей выборкой.
+    pub fn ring(&self) -> &FrodoRing<T, N> {
+        &self.ring
+    }
+
+    /// Возвращает число предложенных элементов.
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    /// Предлагает элемент выборке; `rng` - источник равномерных `u32`, как в `FrodoRing::shuffle`.
+    ///
+    /// Если элемент попал в выборку, вместо случайного старого элемента, возвращает `true`.
+    pub fn offer<R: FnMut() -> u32>(&mut self, item: T, mut rng: R) -> bool {
+        self.seen += 1;
+        let Err(item) = self.ring.push(item) else {
+            return true;
+        };
+
+        let j = random_below_u64(&mut rng, self.seen);
+        if j >= self.ring.len() as u64 {
+            return false;
+        }
+
+        self.ring.remove(j as usize);
+        self.ring.push(item).is_ok()
+    }
+}
+
+/// Очередь, которая при заполнении прореживает себя вдвое и далее принимает лишь каждый `stride`-й элемент,
+/// так что в ней всегда равномерно представлен весь период наблюдения.
+pub struct FrodoDecimator<T, const N: usize> {
+    ring: FrodoRing<T, N>,
+    seen: u64,
+    stride: u64,
+}
+
+impl<T, const N: usize> Default for FrodoDecimator<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            seen: 0,
+            stride: 1,
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoDecimator<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает очередь с текущей выборкой.
+    pub fn ring(&self) -> &FrodoRing<T, N> {
+        &self.ring
+    }
+
+    /// Возвращает текущий шаг прореживания: принимается каждый `stride`-й элемент.
+    pub fn stride(&self) -> u64 {
+        self.stride
+    }
+
+    /// Предлагает элемент; возвращает `true`, если он принят.
+    pub fn offer(&mut self, item: T) -> bool {
+        let index = self.seen;
+        self.seen += 1;
+        if index % self.stride != 0 {
+            return false;
+        }
+
+        let Err(item) = self.ring.push(item) else {
+            return true;
+        };
+
+        // Оставляем каждый второй элемент, удваивая шаг
+        let mut keep = true;
+        for naive_pos in 0..self.ring.used() as isize {
+            if self.ring.at(naive_pos).is_some() {
+                if !keep {
+                    self.ring.remove_at(naive_pos);
+                }
+                keep = !keep;
+            }
+        }
+        self.stride *= 2;
+
+        if index % self.stride != 0 {
+            return false;
+        }
+        self.ring.push(item).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimator_covers_period() {
+        let mut ring = FrodoDecimator::<u32, 4>::new();
+        for i in 0..16 {
+            ring.offer(i);
+        }
+        assert_eq!(ring.stride(), 4);
+        assert_eq!(
+            ring.ring().iter().copied().collect::<Vec<_>>(),
+            [0, 4, 8, 12]
+        );
+    }
+
+    #[test]
+    fn reservoir_keeps_order() {
+        let mut state = 0x2545_f491u32;
+        let mut rng = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+
+        let mut ring = FrodoReservoir::<u32, 8>::new();
+        for i in 0..1000 {
+            ring.offer(i, &mut rng);
+        }
+        assert_eq!(ring.seen(), 1000);
+        assert_eq!(ring.ring().len(), 8);
+
+        let sample = ring.ring().iter().copied().collect::<Vec<_>>();
+        assert!(sample.windows(2).all(|w| w[0] < w[1]));
+        assert!(sample.iter().any(|el| *el >= 500));
+    }
+
+    #[test]
+    fn reservoir_beyond_u32() {
+        let mut ring = FrodoReservoir::<u32, 2>::new();
+        assert!(ring.offer(0x1, || 0));
+        assert!(ring.offer(0x2, || 0));
+        ring.seen = u32::MAX as u64 + 0x10;
+
+        assert!(!ring.offer(0x3, || u32::MAX));
+        assert!(ring.offer(0x4, || 0));
+        assert_eq!(ring.seen(), u32::MAX as u64 + 0x12);
+        assert_eq!(ring.ring().iter().copied().collect::<Vec<_>>(), [0x2, 0x4]);
+    }
+}
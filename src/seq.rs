@@ -0,0 +1,133 @@
+//! Очередь с монотонными порядковыми номерами элементов.
+
+use core::ops::Range;
+
+use crate::FrodoRing;
+
+/// Очередь, присваивающая каждому положенному элементу возрастающий порядковый номер.
+///
+/// В отличие от наивных позиций, номер не меняется при сжатии и служит устойчивым внешним идентификатором.
+pub struct FrodoSeqRing<T, const N: usize> {
+    ring: FrodoRing<(u64, T), N>,
+    next_seq: u64,
+}
+
+impl<T, const N: usize> Default for FrodoSeqRing<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            next_seq: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoSeqRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Находит наивную позицию элемента с номером `seq`.
+    fn naive_pos(&self, seq: u64) -> Option<isize> {
+        self.ring.position(|(s, _)| *s == seq)
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Возвращает номер, который получит следующий положенный элемент.
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Кладёт элемент в очередь и возвращает присвоенный ему номер.
+    pub fn push(&mut self, item: T) -> Result<u64, T> {
+        let seq = self.next_seq;
+        self.ring.push((seq, item)).map_err(|(_, item)| item)?;
+        self.next_seq += 1;
+        Ok(seq)
+    }
+
+    /// Изымает первый элемент очереди вместе с его номером.
+    pub fn pick(&mut self) -> Option<(u64, T)> {
+        self.ring.pick()
+    }
+
+    /// Получает элемент по номеру.
+    pub fn get_by_seq(&self, seq: u64) -> Option<&T> {
+        self.ring.at(self.naive_pos(seq)?).map(|(_, item)| item)
+    }
+
+    /// Изымает элемент по номеру.
+    pub fn remove_by_seq(&mut self, seq: u64) -> Option<T> {
+        let naive_pos = self.naive_pos(seq)?;
+        self.ring.remove_at(naive_pos).map(|(_, item)| item)
+    }
+
+    /// Создаёт итератор по элементам очереди вместе с их номерами.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &T)> {
+        self.ring.iter().map(|(seq, item)| (*seq, item))
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Находит пропуски в порядковых номерах, проставленных производителем, в пределах очереди.
+    ///
+    /// `seq` извлекает номер из элемента. Для каждой пары соседних элементов, номера которых идут не подряд,
+    /// выдаётся диапазон недостающих номеров; номера, идущие не по возрастанию, пропусками не считаются.
+    /// Очередь при этом не изменяется.
+    pub fn detect_gaps<F: Fn(&T) -> u64>(&self, seq: F) -> impl Iterator<Item = Range<u64>> {
+        let mut prev: Option<u64> = None;
+
+        self.iter().filter_map(move |item| {
+            let current = seq(item);
+            let gap = prev
+                .filter(|prev| current > prev.saturating_add(1))
+                .map(|prev| prev + 1..current);
+            prev = Some(current);
+            gap
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seq_survives_compaction() {
+        let mut ring = FrodoSeqRing::<char, 3>::new();
+        assert_eq!(ring.push('a'), Ok(0));
+        assert_eq!(ring.push('b'), Ok(1));
+        assert_eq!(ring.push('c'), Ok(2));
+        assert_eq!(ring.push('d'), Err('d'));
+
+        assert_eq!(ring.remove_by_seq(1), Some('b'));
+        assert_eq!(ring.remove_by_seq(1), None);
+        assert_eq!(ring.push('d'), Ok(3));
+
+        assert_eq!(ring.get_by_seq(2), Some(&'c'));
+        assert_eq!(ring.get_by_seq(3), Some(&'d'));
+        assert_eq!(ring.pick(), Some((0, 'a')));
+        assert_eq!(ring.iter().collect::<Vec<_>>(), [(2, &'c'), (3, &'d')]);
+    }
+
+    #[test]
+    fn detect_gaps() {
+        let mut ring = FrodoRing::<(u64, u8), 6>::new();
+        for seq in [10, 11, 14, 15, 15, 20] {
+            ring.push((seq, 0)).unwrap();
+        }
+
+        let gaps = ring.detect_gaps(|(seq, _)| *seq).collect::<Vec<_>>();
+        assert_eq!(gaps, [12..14, 16..20]);
+        assert_eq!(ring.len(), 6);
+    }
+}
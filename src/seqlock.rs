@@ -0,0 +1,185 @@
+//! Согласованные снимки очереди с одним писателем по схеме seqlock.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering, fence};
+
+use crate::{FrodoRing, Pod};
+
+/// Очередь с одним писателем, состояние которой читатели копируют целиком, не блокируя писателя.
+///
+/// Писатель увеличивает счётчик последовательности до и после каждого изменения; читатель копирует очередь
+/// и повторяет попытку, если за время копирования счётчик изменился. Элементы ограничены `Pod`, чтобы
+/// прерванная копия не давала недопустимых значений. Удобно, например, задаче диагностики, снимающей
+/// состояние очереди, которой владеет прерывание.
+pub struct FrodoSeqlock<T: Pod, const N: usize> {
+    ring: UnsafeCell<FrodoRing<T, N>>,
+    /// Нечётное значение означает, что писатель изменяет очередь.
+    seq: AtomicUsize,
+}
+
+unsafe impl<T: Pod + Send, const N: usize> Sync for FrodoSeqlock<T, N> {}
+
+impl<T: Pod, const N: usize> Default for FrodoSeqlock<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: UnsafeCell::new(FrodoRing::new()),
+            seq: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T: Pod, const N: usize> FrodoSeqlock<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Разделяет очередь на единственного писателя и копируемого читателя.
+    pub fn split(&mut self) -> (FrodoSeqlockWriter<'_, T, N>, FrodoSeqlockReader<'_, T, N>) {
+        (
+            FrodoSeqlockWriter { lock: self },
+            FrodoSeqlockReader { lock: self },
+        )
+    }
+}
+
+/// Сторона писателя `FrodoSeqlock`.
+pub struct FrodoSeqlockWriter<'lock, T: Pod, const N: usize> {
+    lock: &'lock FrodoSeqlock<T, N>,
+}
+
+unsafe impl<T: Pod + Send, const N: usize> Send for FrodoSeqlockWriter<'_, T, N> {}
+
+impl<T: Pod, const N: usize> FrodoSeqlockWriter<'_, T, N> {
+    /// Изменяет очередь; читатели, копировавшие её в это время, повторят попытку.
+    pub fn modify<R, F: FnOnce(&mut FrodoRing<T, N>) -> R>(&mut self, f: F) -> R {
+        let seq = self.lock.seq.load(Ordering::Relaxed);
+        self.lock.seq.store(seq.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        let result = f(unsafe { &mut *self.lock.ring.get() });
+
+        self.lock.seq.store(seq.wrapping_add(2), Ordering::Release);
+        result
+    }
+}
+
+/// Сторона читателя `FrodoSeqlock`; дёшево копируется.
+pub struct FrodoSeqlockReader<'lock, T: Pod, const N: usize> {
+    lock: &'lock FrodoSeqlock<T, N>,
+}
+
+impl<T: Pod, const N: usize> Clone for FrodoSeqlockReader<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Pod, const N: usize> Copy for FrodoSeqlockReader<'_, T, N> {}
+
+unsafe impl<T: Pod + Send, const N: usize> Send for FrodoSeqlockReader<'_, T, N> {}
+
+impl<T: Pod, const N: usize> FrodoSeqlockReader<'_, T, N> {
+    /// Делает одну попытку скопировать очередь в `out`. Возвращает `false`, если писатель мешал копированию.
+    ///
+    /// Копируются только элементы и их расположение: ограничение, пороги заполненности, вытеснение и прочие
+    /// настройки `out` остаются его собственными. Открытая транзакция и резервирования `out` отбрасываются,
+    /// а незаполненные резервирования писателя в копию не попадают.
+    pub fn try_snapshot(&self, out: &mut FrodoRing<T, N>) -> bool {
+        let before = self.lock.seq.load(Ordering::Acquire);
+        if before % 2 == 1 {
+            return false;
+        }
+
+        let copy = unsafe {
+            (self.lock.ring.get() as *const MaybeUninit<FrodoRing<T, N>>).read_volatile()
+        };
+        fence(Ordering::Acquire);
+
+        if self.lock.seq.load(Ordering::Relaxed) != before {
+            return false;
+        }
+
+        let copy = copy.as_ptr();
+        unsafe {
+            out.buffer = (*copy).buffer;
+            out.occupied = (*copy).occupied;
+            out.head = (*copy).head;
+            out.cap = (*copy).cap;
+        }
+        out.staging = None;
+        out.staged = 0;
+        out.reserved = [false; N];
+        out.reservations = 0;
+        out.trim_edges();
+        out.note_depth();
+        true
+    }
+
+    /// Копирует очередь в `out`, повторяя попытки, пока копия не окажется согласованной.
+    pub fn snapshot(&self, out: &mut FrodoRing<T, N>) {
+        while !self.try_snapshot(out) {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seqlock_snapshot() {
+        let mut lock = FrodoSeqlock::<u32, 8>::new();
+        let (mut writer, reader) = lock.split();
+
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..2000u32 {
+                    writer.modify(|ring| {
+                        if ring.push(i).is_err() {
+                            ring.pick();
+                            ring.remove_at(1);
+                            ring.push(i).unwrap();
+                        }
+                    });
+                }
+            });
+
+            let mut out = FrodoRing::new();
+            for _ in 0..2000 {
+                reader.snapshot(&mut out);
+                assert_eq!(out.check_invariants(), Ok(()));
+
+                // Элементы кладутся по возрастанию, так что согласованная копия упорядочена
+                let mut prev = None;
+                for item in out.iter() {
+                    assert!(prev.is_none_or(|prev| prev < *item));
+                    prev = Some(*item);
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn seqlock_snapshot_keeps_config() {
+        let mut lock = FrodoSeqlock::<u32, 4>::new();
+        let (mut writer, reader) = lock.split();
+        writer.modify(|ring| {
+            ring.set_eviction_priority(|_| 0);
+            for i in 0..3 {
+                ring.push(i).unwrap();
+            }
+        });
+
+        let mut out = FrodoRing::new();
+        out.set_limit(3).unwrap();
+        reader.snapshot(&mut out);
+        assert_eq!(out.iter().copied().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(out.limit(), 3);
+        assert!(out.eviction.is_none());
+        assert!(out.push(3).is_err());
+        assert_eq!(out.check_invariants(), Ok(()));
+    }
+}
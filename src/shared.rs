@@ -0,0 +1,132 @@
+//! Очередь, разделяемая между ядрами или контекстами под подключаемой блокировкой.
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::FrodoRing;
+
+/// Блокировка, защищающая `FrodoShared`: аппаратный спинлок (например, SIO на RP2040), семафор-периферия
+/// или отключение прерываний на одноядерной системе.
+///
+/// # Safety
+///
+/// Между возвратом из `lock()` и вызовом `unlock()` никакой другой контекст не должен получить блокировку,
+/// а `unlock()` должен обеспечивать видимость сделанных изменений следующему владельцу (семантика release/acquire).
+pub unsafe trait HardwareLock {
+    /// Ожидает и захватывает блокировку.
+    fn lock(&self);
+
+    /// Освобождает блокировку, захваченную `lock()`.
+    fn unlock(&self);
+}
+
+/// Программный спинлок на `AtomicBool` для платформ с атомарным сравнением с обменом.
+#[derive(Default)]
+pub struct SpinLock {
+    locked: AtomicBool,
+}
+
+impl SpinLock {
+    /// Создаёт свободную блокировку.
+    pub const fn new() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+        }
+    }
+}
+
+unsafe impl HardwareLock for SpinLock {
+    fn lock(&self) {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+    }
+
+    fn unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+    }
+}
+
+/// Очередь, доступ к которой из нескольких ядер или контекстов защищён блокировкой `L`.
+pub struct FrodoShared<L, T, const N: usize> {
+    lock: L,
+    ring: UnsafeCell<FrodoRing<T, N>>,
+}
+
+unsafe impl<L: HardwareLock + Sync, T: Send, const N: usize> Sync for FrodoShared<L, T, N> {}
+
+impl<L: HardwareLock, T, const N: usize> FrodoShared<L, T, N> {
+    /// Создаёт пустую очередь под блокировкой `lock`.
+    pub fn new(lock: L) -> Self {
+        Self {
+            lock,
+            ring: UnsafeCell::new(FrodoRing::new()),
+        }
+    }
+
+    /// Захватывает блокировку и возвращает доступ к очереди; блокировка освобождается при удалении охранника.
+    pub fn lock(&self) -> FrodoSharedGuard<'_, L, T, N> {
+        self.lock.lock();
+        FrodoSharedGuard { shared: self }
+    }
+
+    /// Выполняет `f` над очередью под блокировкой.
+    pub fn with<R, F: FnOnce(&mut FrodoRing<T, N>) -> R>(&self, f: F) -> R {
+        f(&mut self.lock())
+    }
+}
+
+/// Доступ к очереди `FrodoShared` на время удержания блокировки.
+pub struct FrodoSharedGuard<'shared, L: HardwareLock, T, const N: usize> {
+    shared: &'shared FrodoShared<L, T, N>,
+}
+
+impl<L: HardwareLock, T, const N: usize> Deref for FrodoSharedGuard<'_, L, T, N> {
+    type Target = FrodoRing<T, N>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.shared.ring.get() }
+    }
+}
+
+impl<L: HardwareLock, T, const N: usize> DerefMut for FrodoSharedGuard<'_, L, T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.shared.ring.get() }
+    }
+}
+
+impl<L: HardwareLock, T, const N: usize> Drop for FrodoSharedGuard<'_, L, T, N> {
+    fn drop(&mut self) {
+        self.shared.lock.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_across_threads() {
+        let shared = FrodoShared::<_, u32, 64>::new(SpinLock::new());
+
+        std::thread::scope(|s| {
+            for core in 0..2u32 {
+                let shared = &shared;
+                s.spawn(move || {
+                    for i in 0..32 {
+                        shared.with(|ring| ring.push(core * 100 + i)).unwrap();
+                    }
+                });
+            }
+        });
+
+        let ring = shared.lock();
+        assert_eq!(ring.len(), 64);
+        assert_eq!(ring.iter().filter(|el| **el >= 100).count(), 32);
+    }
+}
@@ -0,0 +1,241 @@
+//! Кольцевая очередь малой ёмкости с занятостью в одном машинном слове.
+
+use core::mem::MaybeUninit;
+
+/// Кольцевая очередь с пропусками, как `FrodoRing`, для `N <= 64`.
+///
+/// Занятость ячеек хранится битами одного `u64`, а начало и длина - байтами, поэтому структура заметно меньше
+/// `FrodoRing`, а `push`/`pick`/`position` сводятся к битовым операциям. Подходит для очередей команд на 8-16 элементов.
+pub struct FrodoSmallRing<T, const N: usize> {
+    buffer: [MaybeUninit<T>; N],
+    /// Бит `i` установлен, если занята реальная ячейка `i`.
+    occupied: u64,
+    head: u8,
+    cap: u8,
+}
+
+impl<T, const N: usize> Default for FrodoSmallRing<T, N> {
+    fn default() -> Self {
+        let () = Self::SMALL;
+
+        Self {
+            buffer: [const { MaybeUninit::uninit() }; N],
+            occupied: 0,
+            head: 0,
+            cap: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for FrodoSmallRing<T, N> {
+    fn drop(&mut self) {
+        let mut occupied = self.occupied;
+        while occupied != 0 {
+            let real_pos = occupied.trailing_zeros() as usize;
+            occupied &= occupied - 1;
+            unsafe { self.buffer[real_pos].assume_init_drop() };
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoSmallRing<T, N> {
+    const SMALL: () = assert!(
+        N > 0 && N <= 64,
+        "FrodoSmallRing capacity N must be in 1..=64"
+    );
+
+    /// Маска всех `N` ячеек.
+    const FULL: u64 = if N == 64 { u64::MAX } else { (1 << N) - 1 };
+
+    /// Создаёт новую кольцевую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn real_pos(&self, naive_pos: usize) -> usize {
+        (self.head as usize + naive_pos) % N
+    }
+
+    /// Занятость в наивных позициях: бит `i` соответствует ячейке `i` от начала очереди.
+    fn naive_mask(&self) -> u64 {
+        let head = self.head as usize;
+        if head == 0 {
+            self.occupied
+        } else {
+            ((self.occupied >> head) | (self.occupied << (N - head))) & Self::FULL
+        }
+    }
+
+    fn checked_real_pos(&self, naive_pos: isize) -> Option<usize> {
+        let cap = self.cap as isize;
+        if naive_pos >= cap || naive_pos < -cap {
+            return None;
+        }
+
+        Some(if naive_pos >= 0 {
+            self.real_pos(naive_pos as usize)
+        } else {
+            self.real_pos((cap + naive_pos) as usize)
+        })
+    }
+
+    /// Возвращает использованное число ячеек кольцевой очереди.
+    pub fn used(&self) -> usize {
+        self.cap as usize
+    }
+
+    /// Возвращает число элементов, находящихся в очереди.
+    pub fn len(&self) -> usize {
+        self.occupied.count_ones() as usize
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.occupied == 0
+    }
+
+    /// Кладёт элемент в очередь, при необходимости ужимая пропуски.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.cap as usize == N {
+            if self.occupied == Self::FULL {
+                return Err(item);
+            }
+            self.compact();
+        }
+
+        let real_pos = self.real_pos(self.cap as usize);
+        self.buffer[real_pos].write(item);
+        self.occupied |= 1 << real_pos;
+        self.cap += 1;
+        Ok(())
+    }
+
+    /// Изымает первый элемент очереди.
+    pub fn pick(&mut self) -> Option<T> {
+        self.remove_at(0)
+    }
+
+    /// Получает элемент по ячейке (наивной позиции); отрицательные позиции отсчитываются с конца.
+    pub fn at(&self, naive_pos: isize) -> Option<&T> {
+        let real_pos = self.checked_real_pos(naive_pos)?;
+        (self.occupied & (1 << real_pos) != 0)
+            .then(|| unsafe { self.buffer[real_pos].assume_init_ref() })
+    }
+
+    /// Удаляет содержимое ячейки, находящейся по наивной позиции, и возвращает его.
+    pub fn remove_at(&mut self, naive_pos: isize) -> Option<T> {
+        let real_pos = self.checked_real_pos(naive_pos)?;
+        if self.occupied & (1 << real_pos) == 0 {
+            return None;
+        }
+
+        self.occupied &= !(1 << real_pos);
+        self.trim();
+        Some(unsafe { self.buffer[real_pos].assume_init_read() })
+    }
+
+    /// Получает наивную позицию элемента, отвечающего условию.
+    pub fn position<F: Fn(&T) -> bool>(&self, f: F) -> Option<isize> {
+        let mut mask = self.naive_mask();
+        while mask != 0 {
+            let naive_pos = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            if f(unsafe { self.buffer[self.real_pos(naive_pos)].assume_init_ref() }) {
+                return Some(naive_pos as isize);
+            }
+        }
+        None
+    }
+
+    /// Создаёт итератор по элементам очереди.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut mask = self.naive_mask();
+        core::iter::from_fn(move || {
+            if mask == 0 {
+                return None;
+            }
+            let naive_pos = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            Some(unsafe { self.buffer[self.real_pos(naive_pos)].assume_init_ref() })
+        })
+    }
+
+    /// Сдвигает начало и конец очереди к крайним занятым ячейкам.
+    fn trim(&mut self) {
+        let mask = self.naive_mask();
+        if mask == 0 {
+            self.head = 0;
+            self.cap = 0;
+            return;
+        }
+
+        let skip = mask.trailing_zeros() as usize;
+        self.head = self.real_pos(skip) as u8;
+        self.cap = (64 - mask.leading_zeros()) as u8 - skip as u8;
+    }
+
+    /// Сдвигает элементы к началу очереди, убирая пропуски.
+    fn compact(&mut self) {
+        let mut mask = self.naive_mask();
+        let mut write_pos = 0usize;
+
+        while mask != 0 {
+            let read_pos = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+
+            if read_pos != write_pos {
+                let (from, to) = (self.real_pos(read_pos), self.real_pos(write_pos));
+                let item = unsafe { self.buffer[from].assume_init_read() };
+                self.buffer[to].write(item);
+                self.occupied = (self.occupied & !(1 << from)) | (1 << to);
+            }
+            write_pos += 1;
+        }
+
+        self.cap = write_pos as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_ring() {
+        let mut ring = FrodoSmallRing::<u8, 4>::new();
+        assert!(core::mem::size_of_val(&ring) < core::mem::size_of::<crate::FrodoRing<u8, 4>>());
+
+        for i in 1..=4 {
+            ring.push(i).unwrap();
+        }
+        assert_eq!(ring.push(5), Err(5));
+
+        assert_eq!(ring.pick(), Some(1));
+        assert_eq!(ring.remove_at(1), Some(3));
+        assert_eq!(ring.position(|el| *el == 4), Some(2));
+        ring.push(5).unwrap();
+        ring.push(6).unwrap();
+        assert_eq!(ring.at(-1), Some(&6));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [2, 4, 5, 6]);
+
+        assert_eq!(ring.remove_at(1), Some(4));
+        ring.push(7).unwrap();
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [2, 5, 6, 7]);
+        assert_eq!(ring.len(), 4);
+        assert_eq!(ring.used(), 4);
+    }
+
+    #[test]
+    fn small_ring_full_word() {
+        let mut ring = FrodoSmallRing::<u16, 64>::new();
+        for i in 0..64 {
+            ring.push(i).unwrap();
+        }
+        assert_eq!(ring.remove_at(63), Some(63));
+        assert_eq!(ring.pick(), Some(0));
+        ring.push(64).unwrap();
+        ring.push(65).unwrap();
+        assert_eq!(ring.at(-1), Some(&65));
+        assert_eq!(ring.len(), 64);
+    }
+}
@@ -0,0 +1,267 @@
+//! Снимки состояния очереди: копирование в другую очередь и побайтовая сериализация.
+
+use core::mem::size_of;
+
+use crate::FrodoRing;
+
+/// Типы, любое битовое представление которых допустимо и не содержит байтов выравнивания.
+///
+/// # Safety
+///
+/// Реализация допустима только для типов без байтов выравнивания, для которых корректна любая последовательность байтов.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+unsafe impl<T: Pod, const M: usize> Pod for [T; M] {}
+
+impl<T: Clone, const N: usize> Clone for FrodoRing<T, N> {
+    fn clone(&self) -> Self {
+        let mut ring = Self::new();
+        self.snapshot_into(&mut ring);
+        ring
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        source.snapshot_into(self);
+    }
+}
+
+impl<T: Clone, const N: usize> FrodoRing<T, N> {
+    /// Копирует состояние очереди в `dst`, сохраняя расположение элементов по ячейкам.
+    ///
+    /// Прежнее содержимое `dst` удаляется. Отложенные в открытой транзакции элементы не копируются.
+    pub fn snapshot_into(&self, dst: &mut Self) {
+        dst.clear();
+
+        for i in 0..N {
+            if self.occupied[i] {
+                dst.buffer[i].write(unsafe { self.buffer[i].assume_init_ref() }.clone());
+                dst.occupied[i] = true;
+            }
+        }
+
+        dst.head = self.head;
+        dst.cap = self.cap;
+        dst.limit = self.limit;
+        // Резервирования не копируются: на их месте в копии остаются пустые ячейки
+        dst.trim();
+        dst.note_depth();
+    }
+}
+
+impl<T: Pod, const N: usize> FrodoRing<T, N> {
+    /// Размер побайтового снимка очереди: `head` и `cap` (по 4 байта, LE), карта занятости (`N` байт) и ячейки.
+    pub const SNAPSHOT_SIZE: usize = 8 + N + N * size_of::<T>();
+
+    /// Записывает побайтовый снимок очереди в `out`, возвращая число записанных байт.
+    ///
+    /// Пустые ячейки заполняются нулями. Возвращает `None`, если `out` короче `Self::SNAPSHOT_SIZE`.
+    pub fn snapshot_bytes(&self, out: &mut [u8]) -> Option<usize> {
+        let out = out.get_mut(..Self::SNAPSHOT_SIZE)?;
+        let (header, rest) = out.split_at_mut(8);
+        let (occupied, cells) = rest.split_at_mut(N);
+
+        // Резервирования не сохраняются, поэтому края очереди считаются только по занятым ячейкам
+        let mut head = self.head;
+        let mut cap = self.cap;
+        while cap > 0 && !self.occupied[head] {
+            head = (head + 1) % N;
+            cap -= 1;
+        }
+        while cap > 0 && !self.occupied[(head + cap - 1) % N] {
+            cap -= 1;
+        }
+
+        header[..4].copy_from_slice(&(head as u32).to_le_bytes());
+        header[4..].copy_from_slice(&(cap as u32).to_le_bytes());
+
+        for i in 0..N {
+            let cell = &mut cells[i * size_of::<T>()..(i + 1) * size_of::<T>()];
+            occupied[i] = self.occupied[i] as u8;
+            if self.occupied[i] {
+                let item = unsafe { self.buffer[i].assume_init_ref() };
+                let bytes = unsafe {
+                    core::slice::from_raw_parts((item as *const T).cast::<u8>(), size_of::<T>())
+                };
+                cell.copy_from_slice(bytes);
+            } else {
+                cell.fill(0);
+            }
+        }
+
+        Some(Self::SNAPSHOT_SIZE)
+    }
+
+    /// Восстанавливает очередь из побайтового снимка, сделанного `snapshot_bytes()`.
+    ///
+    /// Возвращает `None`, если снимок слишком короткий или описывает некорректное состояние очереди.
+    pub fn restore_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..Self::SNAPSHOT_SIZE)?;
+        let (header, rest) = bytes.split_at(8);
+        let (occupied, cells) = rest.split_at(N);
+
+        let head = u32::from_le_bytes(header[..4].try_into().ok()?) as usize;
+        let cap = u32::from_le_bytes(header[4..].try_into().ok()?) as usize;
+
+        if cap > N || (N > 0 && head >= N) || occupied.iter().any(|o| *o > 1) {
+            return None;
+        }
+
+        let mut ring = Self::new();
+        ring.head = head;
+
+        for i in 0..N {
+            let cell = &cells[i * size_of::<T>()..(i + 1) * size_of::<T>()];
+            if occupied[i] == 1 {
+                let item = unsafe { core::ptr::read_unaligned(cell.as_ptr().cast::<T>()) };
+                ring.buffer[i].write(item);
+                ring.occupied[i] = true;
+            }
+        }
+
+        ring.cap = cap;
+        ring.check_invariants().ok()?;
+        Some(ring)
+    }
+
+    /// Размер поэлементного снимка очереди: `N` записей из ячейки и её CRC-32 (4 байта, LE).
+    pub const ENTRIES_SIZE: usize = N * (size_of::<T>() + 4);
+
+    /// Записывает элементы очереди по порядку в `out` в виде записей с контрольной суммой.
+    ///
+    /// Неиспользованные записи заполняются `0xFF`, как стёртая флеш-память. Пропуски в очереди не сохраняются.
+    /// Возвращает `None`, если `out` короче `Self::ENTRIES_SIZE`.
+    pub fn snapshot_entries(&self, out: &mut [u8]) -> Option<usize> {
+        let out = out.get_mut(..Self::ENTRIES_SIZE)?;
+        out.fill(0xFF);
+
+        for (entry, item) in out.chunks_exact_mut(size_of::<T>() + 4).zip(self.iter()) {
+            let (cell, crc) = entry.split_at_mut(size_of::<T>());
+            let bytes = unsafe {
+                core::slice::from_raw_parts((item as *const T).cast::<u8>(), size_of::<T>())
+            };
+            cell.copy_from_slice(bytes);
+            crc.copy_from_slice(&crc32(bytes).to_le_bytes());
+        }
+
+        Some(Self::ENTRIES_SIZE)
+    }
+
+    /// Восстанавливает очередь из поэлементного снимка, сделанного `snapshot_entries()`.
+    ///
+    /// Записи с неверной контрольной суммой (оборванные при пропадании питания или повреждённые) пропускаются,
+    /// а не отвергают весь снимок. Возвращает очередь и число пропущенных записей; стёртые записи не считаются.
+    /// Возвращает `None`, если снимок слишком короткий.
+    pub fn restore_entries(bytes: &[u8]) -> Option<(Self, usize)> {
+        let bytes = bytes.get(..Self::ENTRIES_SIZE)?;
+        let mut ring = Self::new();
+        let mut dropped = 0;
+
+        for entry in bytes.chunks_exact(size_of::<T>() + 4) {
+            if entry.iter().all(|b| *b == 0xFF) {
+                continue;
+            }
+
+            let (cell, crc) = entry.split_at(size_of::<T>());
+            if crc32(cell).to_le_bytes() != crc {
+                dropped += 1;
+                continue;
+            }
+
+            let item = unsafe { core::ptr::read_unaligned(cell.as_ptr().cast::<T>()) };
+            ring.push(item).ok()?;
+        }
+
+        Some((ring, dropped))
+    }
+}
+
+/// CRC-32 (IEEE 802.3) без таблицы, чтобы не занимать флеш-память.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_into() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x2));
+
+        let mut copy = FrodoRing::<u8, 4>::new();
+        assert!(copy.push(0x9).is_ok());
+        ring.snapshot_into(&mut copy);
+
+        assert_eq!(copy.used(), 3);
+        assert_eq!(copy.at(0), Some(&0x1));
+        assert_eq!(copy.at(1), None);
+        assert_eq!(copy.at(2), Some(&0x3));
+
+        let clone = ring.clone();
+        assert_eq!(clone.len(), 2);
+        assert_eq!(clone.get(1), Some(&0x3));
+    }
+
+    #[test]
+    fn snapshot_bytes() {
+        let mut ring = FrodoRing::<u16, 4>::new();
+        assert!(ring.push(0x101).is_ok());
+        assert!(ring.push(0x202).is_ok());
+        assert!(ring.push(0x303).is_ok());
+        assert_eq!(ring.pick(), Some(0x101));
+        assert_eq!(ring.remove_at(0), Some(0x202));
+        assert!(ring.push(0x404).is_ok());
+
+        let mut bytes = [0u8; FrodoRing::<u16, 4>::SNAPSHOT_SIZE];
+        assert_eq!(ring.snapshot_bytes(&mut bytes[..4]), None);
+        assert_eq!(ring.snapshot_bytes(&mut bytes), Some(20));
+
+        let restored = FrodoRing::<u16, 4>::restore_bytes(&bytes).unwrap();
+        assert_eq!(restored.used(), 2);
+        assert_eq!(restored.at(0), Some(&0x303));
+        assert_eq!(restored.at(1), Some(&0x404));
+
+        bytes[8] = 1;
+        assert!(FrodoRing::<u16, 4>::restore_bytes(&bytes).is_none());
+    }
+
+    #[test]
+    fn restore_entries() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let mut ring = FrodoRing::<u32, 4>::new();
+        for i in 1..=3 {
+            assert!(ring.push(i * 0x1111).is_ok());
+        }
+
+        let mut bytes = [0u8; FrodoRing::<u32, 4>::ENTRIES_SIZE];
+        assert_eq!(ring.snapshot_entries(&mut bytes), Some(32));
+
+        // Оборванная запись посередине
+        bytes[9] ^= 0x40;
+        let (restored, dropped) = FrodoRing::<u32, 4>::restore_entries(&bytes).unwrap();
+        assert_eq!(dropped, 1);
+        assert!(restored.iter().eq([&0x1111, &0x3333]));
+    }
+}
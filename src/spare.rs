@@ -0,0 +1,97 @@
+//! Прямой доступ к свободным ячейкам, по аналогии с `Vec::spare_capacity_mut`.
+
+use core::mem::MaybeUninit;
+
+use crate::FrodoRing;
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает непрерывный участок свободных ячеек сразу за концом очереди.
+    ///
+    /// Участок не переходит через конец буфера и учитывает ограничение ёмкости; пропуски внутри очереди
+    /// в него не входят. Во время транзакции возвращается пустой срез. Заполненные ячейки делаются видимыми
+    /// вызовом `assume_pushed()`.
+    pub fn spare_capacity(&mut self) -> &mut [MaybeUninit<T>] {
+        if self.staging.is_some() {
+            return &mut [];
+        }
+        if self.cap == 0 {
+            self.head = 0;
+        }
+
+        let tail = self.real_pos(self.cap);
+        let count = self.contiguous_free_space();
+        &mut self.buffer[tail..tail + count]
+    }
+
+    /// Возвращает длину наибольшего непрерывного участка, который можно заполнить напрямую
+    /// (например, через DMA) и сделать видимым `assume_pushed()`; это длина среза из `spare_capacity()`.
+    pub fn contiguous_free_space(&self) -> usize {
+        if self.staging.is_some() {
+            return 0;
+        }
+
+        // Пустая очередь начинается с первой ячейки буфера
+        let tail = if self.cap == 0 {
+            0
+        } else {
+            self.real_pos(self.cap)
+        };
+        (N - self.cap)
+            .min(N - tail)
+            .min(self.limit.saturating_sub(self.len() + self.reservations))
+    }
+
+    /// Делает видимыми первые `n` ячеек, полученных через `spare_capacity()`.
+    ///
+    /// # Safety
+    ///
+    /// `n` не должно превышать длину последнего среза из `spare_capacity()`, и все `n` ячеек должны быть
+    /// инициализированы; между этими вызовами очередь не должна изменяться.
+    pub unsafe fn assume_pushed(&mut self, n: usize) {
+        for naive_pos in self.cap..self.cap + n {
+            let real_pos = self.real_pos(naive_pos);
+            self.occupied[real_pos] = true;
+        }
+        self.cap += n;
+        #[cfg(feature = "journal")]
+        if n > 0 {
+            self.record(crate::journal::JournalOp::PushMany(n), self.cap - n);
+        }
+        self.check_watermarks();
+        self.note_depth();
+        #[cfg(feature = "stats")]
+        self.sample_depths(self.len() - n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FrodoRing;
+
+    #[test]
+    fn spare_capacity() {
+        let mut ring = FrodoRing::<u16, 6>::new();
+        for i in 0..4 {
+            ring.push(i).unwrap();
+        }
+        ring.pick();
+        ring.pick();
+
+        let spare = ring.spare_capacity();
+        assert_eq!(spare.len(), 2);
+        spare[0].write(10);
+        spare[1].write(11);
+        unsafe { ring.assume_pushed(2) };
+
+        // Оставшиеся свободные ячейки лежат в начале буфера
+        let spare = ring.spare_capacity();
+        assert_eq!(spare.len(), 2);
+        spare[0].write(12);
+        unsafe { ring.assume_pushed(1) };
+
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [2, 3, 10, 11, 12]);
+
+        ring.set_limit(5).unwrap();
+        assert!(ring.spare_capacity().is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+//! Разделение очереди на две непересекающиеся части по логической позиции.
+
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+use crate::FrodoRing;
+
+/// Часть очереди, доступная только для чтения.
+///
+/// Охватывает диапазон ячеек `[start, end)` в наивных позициях исходной очереди.
+pub struct FrodoRingSplit<'ring, T, const N: usize> {
+    ring: &'ring FrodoRing<T, N>,
+    start: usize,
+    end: usize,
+}
+
+impl<'ring, T, const N: usize> FrodoRingSplit<'ring, T, N> {
+    /// Возвращает число элементов в части очереди.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Сообщает, есть ли в части очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Получает элемент по очереди внутри части.
+    pub fn get(&self, pos: usize) -> Option<&'ring T> {
+        self.iter().nth(pos)
+    }
+
+    /// Создаёт итератор по элементам части очереди.
+    pub fn iter(&self) -> impl Iterator<Item = &'ring T> + use<'ring, T, N> {
+        let ring = self.ring;
+        (self.start..self.end).filter_map(move |naive_pos| ring.at(naive_pos as isize))
+    }
+}
+
+/// Часть очереди, доступная для изменения элементов.
+///
+/// Две части, полученные из `split_at_mut()`, не пересекаются по ячейкам и могут обрабатываться независимо.
+pub struct FrodoRingSplitMut<'ring, T, const N: usize> {
+    buffer: *mut MaybeUninit<T>,
+    occupied: &'ring [bool; N],
+    head: usize,
+    start: usize,
+    end: usize,
+    _marker: PhantomData<&'ring mut T>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for FrodoRingSplitMut<'_, T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for FrodoRingSplitMut<'_, T, N> {}
+
+impl<T, const N: usize> FrodoRingSplitMut<'_, T, N> {
+    /// Возвращает реальные позиции занятых ячеек части очереди.
+    fn real_positions(&self) -> impl Iterator<Item = usize> + use<'_, T, N> {
+        (self.start..self.end)
+            .map(|naive_pos| (self.head + naive_pos) % N)
+            .filter(|real_pos| self.occupied[*real_pos])
+    }
+
+    /// Возвращает число элементов в части очереди.
+    pub fn len(&self) -> usize {
+        self.real_positions().count()
+    }
+
+    /// Сообщает, есть ли в части очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.real_positions().next().is_none()
+    }
+
+    /// Получает элемент по очереди внутри части.
+    pub fn get(&self, pos: usize) -> Option<&T> {
+        let real_pos = self.real_positions().nth(pos)?;
+        Some(unsafe { (*self.buffer.add(real_pos)).assume_init_ref() })
+    }
+
+    /// Получает изменяемую ссылку на элемент по очереди внутри части.
+    pub fn get_mut(&mut self, pos: usize) -> Option<&mut T> {
+        let real_pos = self.real_positions().nth(pos)?;
+        Some(unsafe { (*self.buffer.add(real_pos)).assume_init_mut() })
+    }
+
+    /// Создаёт итератор по элементам части очереди.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let buffer = self.buffer;
+        self.real_positions()
+            .map(move |real_pos| unsafe { (*buffer.add(real_pos)).assume_init_ref() })
+    }
+
+    /// Создаёт итератор по изменяемым ссылкам на элементы части очереди.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let buffer = self.buffer;
+        self.real_positions()
+            .map(move |real_pos| unsafe { (*buffer.add(real_pos)).assume_init_mut() })
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает наивную позицию, с которой начинается элемент `pos` по очереди, или `self.used()`.
+    fn split_point(&self, pos: usize) -> usize {
+        (0..self.cap)
+            .filter(|naive_pos| self.occupied[self.real_pos(*naive_pos)])
+            .nth(pos)
+            .unwrap_or(self.cap)
+    }
+
+    /// Разделяет очередь на старшую часть (первые `pos` элементов) и младшую часть (остальные элементы).
+    pub fn split_at(&self, pos: usize) -> (FrodoRingSplit<'_, T, N>, FrodoRingSplit<'_, T, N>) {
+        let mid = self.split_point(pos);
+        (
+            FrodoRingSplit {
+                ring: self,
+                start: 0,
+                end: mid,
+            },
+            FrodoRingSplit {
+                ring: self,
+                start: mid,
+                end: self.cap,
+            },
+        )
+    }
+
+    /// Разделяет очередь на две изменяемые части; см. `split_at()`.
+    pub fn split_at_mut(
+        &mut self,
+        pos: usize,
+    ) -> (FrodoRingSplitMut<'_, T, N>, FrodoRingSplitMut<'_, T, N>) {
+        let mid = self.split_point(pos);
+        let (head, cap) = (self.head, self.cap);
+        let buffer = self.buffer.as_mut_ptr();
+        let occupied = &self.occupied;
+        (
+            FrodoRingSplitMut {
+                buffer,
+                occupied,
+                head,
+                start: 0,
+                end: mid,
+                _marker: PhantomData,
+            },
+            FrodoRingSplitMut {
+                buffer,
+                occupied,
+                head,
+                start: mid,
+                end: cap,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_at() {
+        let mut ring = FrodoRing::<u8, 5>::new();
+        for i in 0x1..=0x5 {
+            assert!(ring.push(i).is_ok());
+        }
+        assert_eq!(ring.remove_at(1), Some(0x2));
+
+        let (front, back) = ring.split_at(2);
+        assert_eq!(front.len(), 2);
+        assert_eq!(front.get(1), Some(&0x3));
+        assert_eq!(back.iter().copied().collect::<Vec<_>>(), [0x4, 0x5]);
+
+        let (front, back) = ring.split_at(10);
+        assert_eq!(front.len(), 4);
+        assert!(back.is_empty());
+    }
+
+    #[test]
+    fn split_at_mut() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        for i in 0x1..=0x4 {
+            assert!(ring.push(i).is_ok());
+        }
+
+        let (mut front, mut back) = ring.split_at_mut(1);
+        front.iter_mut().for_each(|el| *el += 0x10);
+        *back.get_mut(2).unwrap() += 0x20;
+        assert_eq!(back.len(), 3);
+        assert_eq!(back.get(0), Some(&0x2));
+
+        assert_eq!(ring.get(0), Some(&0x11));
+        assert_eq!(ring.get(3), Some(&0x24));
+    }
+}
@@ -0,0 +1,60 @@
+//! Макрос для объявления `'static` очередей, разделяемых между `main` и обработчиками прерываний.
+
+/// Объявляет статическую очередь и тип-ключ с методом `take()`, выдающим `&'static mut` на неё ровно один раз.
+///
+/// Очередь создаётся через `Default` при первом вызове `take()`; все последующие вызовы возвращают `None`.
+/// Полученную ссылку можно сразу разделить, например `IsrRing::split()`, и раздать половины `main` и прерыванию.
+///
+/// ```
+/// use frodo_ring::{frodo_static, IsrRing};
+///
+/// frodo_static!(UART_RX: IsrRing<u8, 64>);
+///
+/// let (mut producer, mut consumer) = UART_RX::take().unwrap().split();
+/// assert!(UART_RX::take().is_none());
+///
+/// producer.push(0x42).unwrap();
+/// assert_eq!(consumer.poll(), Some(0x42));
+/// ```
+///
+/// Требует атомарного `swap` для `AtomicBool`, доступного на целевой платформе.
+#[macro_export]
+macro_rules! frodo_static {
+    ($vis:vis $name:ident : $ty:ty) => {
+        #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+        $vis struct $name;
+
+        impl $name {
+            /// Выдаёт статическую очередь; возвращает `None` при повторном вызове.
+            #[allow(dead_code)]
+            $vis fn take() -> ::core::option::Option<&'static mut $ty> {
+                static TAKEN: ::core::sync::atomic::AtomicBool = ::core::sync::atomic::AtomicBool::new(false);
+                static mut STORAGE: ::core::mem::MaybeUninit<$ty> = ::core::mem::MaybeUninit::uninit();
+
+                if TAKEN.swap(true, ::core::sync::atomic::Ordering::AcqRel) {
+                    return ::core::option::Option::None;
+                }
+
+                // Флаг гарантирует, что ссылка на хранилище выдаётся единственный раз
+                let storage = unsafe { &mut *(&raw mut STORAGE) };
+                ::core::option::Option::Some(storage.write(<$ty as ::core::default::Default>::default()))
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FrodoRing;
+
+    frodo_static!(QUEUE: FrodoRing<u32, 4>);
+
+    #[test]
+    fn static_take_once() {
+        let ring = QUEUE::take().unwrap();
+        assert!(QUEUE::take().is_none());
+
+        ring.push(1).unwrap();
+        assert_eq!(ring.pick(), Some(1));
+    }
+}
@@ -0,0 +1,87 @@
+//! Статистика распределения глубины очереди.
+
+use crate::FrodoRing;
+
+/// Число корзин гистограммы глубины.
+pub(crate) const DEPTH_BUCKETS: usize = 8;
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает гистограмму глубины очереди, наблюдаемой после каждого `push()` и `pick()`.
+    ///
+    /// Пакетные операции (`push_slice()`, `pick_slice()`, `pick_batch()`, `commit()`) учитываются поэлементно.
+    ///
+    /// Глубины `0..=N` делятся на 8 равных корзин: глубина `len` попадает в корзину `len * 8 / (N + 1)`.
+    /// Счётчики переполняются через ноль. В отличие от максимума, распределение показывает, насколько часто
+    /// очередь действительно близка к заполнению, что полезно при подборе ёмкости.
+    pub fn depth_histogram(&self) -> [u32; DEPTH_BUCKETS] {
+        self.depth_histogram
+    }
+
+    /// Обнуляет гистограмму глубины.
+    pub fn reset_depth_histogram(&mut self) {
+        self.depth_histogram = [0; DEPTH_BUCKETS];
+    }
+
+    /// Учитывает текущую глубину очереди в гистограмме.
+    pub(crate) fn sample_depth(&mut self) {
+        self.count_depth(self.len());
+    }
+
+    /// Учитывает все глубины, пройденные очередью от глубины `from` до текущей,
+    /// как если бы элементы добавлялись или изымались по одному.
+    pub(crate) fn sample_depths(&mut self, from: usize) {
+        let len = self.len();
+        let depths = if from < len {
+            from + 1..len + 1
+        } else {
+            len..from
+        };
+        for depth in depths {
+            self.count_depth(depth);
+        }
+    }
+
+    fn count_depth(&mut self, depth: usize) {
+        let bucket = depth * DEPTH_BUCKETS / (N + 1);
+        self.depth_histogram[bucket] = self.depth_histogram[bucket].wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_histogram() {
+        let mut ring = FrodoRing::<u8, 7>::new();
+        for i in 0..7 {
+            assert!(ring.push(i).is_ok());
+        }
+        assert_eq!(ring.depth_histogram(), [0, 1, 1, 1, 1, 1, 1, 1]);
+
+        assert_eq!(ring.pick(), Some(0));
+        assert_eq!(ring.pick(), Some(1));
+        assert_eq!(ring.depth_histogram(), [0, 1, 1, 1, 1, 2, 2, 1]);
+
+        ring.reset_depth_histogram();
+        ring.clear();
+        assert_eq!(ring.push_slice(&[0, 1, 2, 3, 4, 5, 6]), 7);
+        assert_eq!(ring.depth_histogram(), [0, 1, 1, 1, 1, 1, 1, 1]);
+        assert_eq!(ring.pick_slice(&mut [0; 2]), 2);
+        assert_eq!(ring.depth_histogram(), [0, 1, 1, 1, 1, 2, 2, 1]);
+        assert_eq!(ring.pick_batch(5, |batch| batch.len()), 5);
+        assert_eq!(ring.depth_histogram(), [1, 2, 2, 2, 2, 2, 2, 1]);
+
+        ring.begin();
+        assert!(ring.push(0).is_ok());
+        assert!(ring.push(1).is_ok());
+        assert_eq!(ring.depth_histogram(), [1, 2, 2, 2, 2, 2, 2, 1]);
+        ring.commit();
+        assert_eq!(ring.depth_histogram(), [1, 3, 3, 2, 2, 2, 2, 1]);
+
+        ring.reset_depth_histogram();
+        ring.clear();
+        assert_eq!(ring.pick(), None);
+        assert_eq!(ring.depth_histogram(), [0; DEPTH_BUCKETS]);
+    }
+}
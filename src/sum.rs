@@ -0,0 +1,148 @@
+//! Очередь чисел с текущей суммой и средним за `O(1)`.
+
+use core::ops::{Add, Sub};
+
+use crate::FrodoRing;
+
+/// Числа, для которых поддерживается текущая сумма в более широком типе.
+pub trait Summable: Copy {
+    /// Тип суммы: достаточно широкий, чтобы не переполняться на `N` элементах.
+    type Sum: Copy + Default + Add<Output = Self::Sum> + Sub<Output = Self::Sum>;
+
+    /// Расширяет значение до типа суммы.
+    fn widen(self) -> Self::Sum;
+
+    /// Делит сумму на число элементов (для целых - с округлением к нулю).
+    fn mean(sum: Self::Sum, count: usize) -> Self::Sum;
+}
+
+macro_rules! summable {
+    ($($ty:ty => $sum:ty),*) => {
+        $(
+            impl Summable for $ty {
+                type Sum = $sum;
+
+                fn widen(self) -> $sum {
+                    self as $sum
+                }
+
+                fn mean(sum: $sum, count: usize) -> $sum {
+                    sum / count as $sum
+                }
+            }
+        )*
+    };
+}
+
+summable!(
+    u8 => u64, u16 => u64, u32 => u64, u64 => u128, usize => u128,
+    i8 => i64, i16 => i64, i32 => i64, i64 => i128, isize => i128,
+    f32 => f64, f64 => f64
+);
+
+/// Очередь чисел, обновляющая сумму элементов при каждом добавлении и изъятии.
+///
+/// Для чисел с плавающей точкой вычитание накапливает погрешность; `resync()` пересчитывает сумму заново.
+pub struct FrodoSumRing<T: Summable, const N: usize> {
+    ring: FrodoRing<T, N>,
+    sum: T::Sum,
+}
+
+impl<T: Summable, const N: usize> Default for FrodoSumRing<T, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            sum: T::Sum::default(),
+        }
+    }
+}
+
+impl<T: Summable, const N: usize> FrodoSumRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает очередь с элементами.
+    pub fn ring(&self) -> &FrodoRing<T, N> {
+        &self.ring
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Кладёт элемент в очередь, добавляя его к сумме.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        self.ring.push(item)?;
+        self.sum = self.sum + item.widen();
+        Ok(())
+    }
+
+    /// Отдаёт первый элемент, вычитая его из суммы.
+    pub fn pick(&mut self) -> Option<T> {
+        let item = self.ring.pick()?;
+        self.sum = self.sum - item.widen();
+        Some(item)
+    }
+
+    /// Изымает элемент по ячейке (наивной позиции), вычитая его из суммы.
+    pub fn remove_at(&mut self, naive_pos: isize) -> Option<T> {
+        let item = self.ring.remove_at(naive_pos)?;
+        self.sum = self.sum - item.widen();
+        Some(item)
+    }
+
+    /// Возвращает сумму элементов очереди.
+    pub fn sum(&self) -> T::Sum {
+        self.sum
+    }
+
+    /// Возвращает среднее элементов очереди.
+    pub fn mean(&self) -> Option<T::Sum> {
+        match self.ring.len() {
+            0 => None,
+            len => Some(T::mean(self.sum, len)),
+        }
+    }
+
+    /// Пересчитывает сумму по элементам очереди, сбрасывая накопленную погрешность.
+    pub fn resync(&mut self) {
+        self.sum = self
+            .ring
+            .iter()
+            .fold(T::Sum::default(), |sum, item| sum + item.widen());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_sum() {
+        let mut ring = FrodoSumRing::<u8, 4>::new();
+        assert_eq!(ring.mean(), None);
+        for sample in [200, 250, 100, 90] {
+            assert!(ring.push(sample).is_ok());
+        }
+        assert_eq!(ring.sum(), 640);
+        assert_eq!(ring.mean(), Some(160));
+
+        assert_eq!(ring.pick(), Some(200));
+        assert_eq!(ring.remove_at(-1), Some(90));
+        assert_eq!(ring.sum(), 350);
+
+        let mut floats = FrodoSumRing::<f32, 2>::new();
+        assert!(floats.push(0.5).is_ok());
+        assert!(floats.push(1.5).is_ok());
+        floats.resync();
+        assert_eq!(floats.mean(), Some(1.0));
+    }
+}
@@ -0,0 +1,116 @@
+//! Очередь с горячим кольцом фиксированного размера и переполнением в кучу (при включённой функции `alloc`).
+
+use alloc::collections::VecDeque;
+
+use crate::FrodoRing;
+
+/// Очередь, хранящая элементы в `FrodoRing`, а при его заполнении - во вторичной очереди в куче.
+///
+/// Порядок FIFO сохраняется: пока во вторичной очереди есть элементы, новые элементы попадают туда же,
+/// а по мере освобождения места в кольце оно пополняется из вторичной очереди.
+pub struct FrodoTieredRing<T, const N: usize> {
+    hot: FrodoRing<T, N>,
+    overflow: VecDeque<T>,
+}
+
+impl<T, const N: usize> Default for FrodoTieredRing<T, N> {
+    fn default() -> Self {
+        Self {
+            hot: FrodoRing::new(),
+            overflow: VecDeque::new(),
+        }
+    }
+}
+
+impl<T, const N: usize> FrodoTieredRing<T, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает горячее кольцо.
+    pub fn hot(&self) -> &FrodoRing<T, N> {
+        &self.hot
+    }
+
+    /// Возвращает число элементов, вытесненных во вторичную очередь.
+    pub fn spilled(&self) -> usize {
+        self.overflow.len()
+    }
+
+    /// Возвращает общее число элементов.
+    pub fn len(&self) -> usize {
+        self.hot.len() + self.overflow.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.hot.is_empty() && self.overflow.is_empty()
+    }
+
+    /// Кладёт элемент в очередь; если кольцо заполнено, элемент уходит во вторичную очередь.
+    pub fn push(&mut self, item: T) {
+        if !self.overflow.is_empty() {
+            self.overflow.push_back(item);
+            return;
+        }
+
+        if let Err(item) = self.hot.push(item) {
+            self.overflow.push_back(item);
+        }
+    }
+
+    /// Изымает первый элемент очереди.
+    pub fn pick(&mut self) -> Option<T> {
+        let item = self.hot.pick();
+        self.refill();
+        item
+    }
+
+    /// Удаляет содержимое ячейки горячего кольца по наивной позиции; см. `FrodoRing::remove_at`.
+    pub fn remove_at(&mut self, naive_pos: isize) -> Option<T> {
+        let item = self.hot.remove_at(naive_pos);
+        self.refill();
+        item
+    }
+
+    /// Создаёт итератор по всем элементам в порядке очереди.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.hot.iter().chain(self.overflow.iter())
+    }
+
+    /// Переносит элементы из вторичной очереди в освободившееся место кольца.
+    fn refill(&mut self) {
+        while let Some(item) = self.overflow.pop_front() {
+            if let Err(item) = self.hot.push(item) {
+                self.overflow.push_front(item);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiered_spill_and_refill() {
+        let mut ring = FrodoTieredRing::<u8, 3>::new();
+        for i in 1..=5 {
+            ring.push(i);
+        }
+        assert_eq!(ring.hot().len(), 3);
+        assert_eq!(ring.spilled(), 2);
+
+        assert_eq!(ring.remove_at(1), Some(2));
+        assert_eq!(ring.spilled(), 1);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [1, 3, 4, 5]);
+
+        assert_eq!(ring.pick(), Some(1));
+        assert_eq!(ring.spilled(), 0);
+        ring.push(6);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [3, 4, 5, 6]);
+        assert_eq!(ring.len(), 4);
+    }
+}
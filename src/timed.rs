@@ -0,0 +1,141 @@
+//! Пользовательские часы и учёт времени ожидания элементов в очереди.
+
+use crate::FrodoRing;
+
+/// Источник монотонного времени в тиках (например, счётчик SysTick или DWT CYCCNT).
+///
+/// Тики могут переполняться через ноль: возраст считается по разности с переносом в пределах `WRAP_MASK`.
+pub trait TimeSource {
+    /// Маска разрядов счётчика: для 32-битного счётчика, расширенного до `u64`, - `u32::MAX as u64`.
+    const WRAP_MASK: u64 = u64::MAX;
+
+    /// Возвращает текущее время в тиках.
+    fn now(&self) -> u64;
+
+    /// Возвращает число тиков, прошедших с момента `since`, с учётом переполнения счётчика.
+    fn elapsed(&self, since: u64) -> u64 {
+        self.now().wrapping_sub(since) & Self::WRAP_MASK
+    }
+}
+
+impl<F: Fn() -> u64> TimeSource for F {
+    fn now(&self) -> u64 {
+        self()
+    }
+}
+
+/// Очередь, запоминающая время постановки каждого элемента по часам `C`.
+///
+/// Позволяет узнать возраст элемента (`age()`) и задержку в очереди при выдаче (`pick()`),
+/// не оборачивая каждый элемент в структуру с отметкой времени вручную.
+pub struct FrodoTimedRing<C, T, const N: usize> {
+    /// Элементы с временем постановки.
+    ring: FrodoRing<(u64, T), N>,
+    clock: C,
+    max_latency: u64,
+}
+
+impl<C: TimeSource, T, const N: usize> FrodoTimedRing<C, T, N> {
+    /// Создаёт новую очередь с часами `clock`.
+    pub fn new(clock: C) -> Self {
+        Self {
+            ring: FrodoRing::new(),
+            clock,
+            max_latency: 0,
+        }
+    }
+
+    /// Возвращает число элементов в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Кладёт элемент в очередь, запоминая время постановки.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let now = self.clock.now();
+        self.ring.push((now, item)).map_err(|(_, item)| item)
+    }
+
+    /// Получает элемент по очереди.
+    pub fn get(&self, pos: usize) -> Option<&T> {
+        self.ring.get(pos).map(|(_, item)| item)
+    }
+
+    /// Возвращает возраст элемента по очереди в тиках: сколько он уже ждёт.
+    pub fn age(&self, pos: usize) -> Option<u64> {
+        let (enqueued, _) = self.ring.get(pos)?;
+        Some(self.clock.elapsed(*enqueued))
+    }
+
+    /// Отдаёт первый элемент вместе с задержкой в очереди в тиках.
+    pub fn pick(&mut self) -> Option<(T, u64)> {
+        let (enqueued, item) = self.ring.pick()?;
+        let latency = self.clock.elapsed(enqueued);
+        self.max_latency = self.max_latency.max(latency);
+        Some((item, latency))
+    }
+
+    /// Возвращает наибольшую задержку выданных элементов с момента создания или `reset_max_latency()`.
+    pub fn max_latency(&self) -> u64 {
+        self.max_latency
+    }
+
+    /// Сбрасывает наибольшую задержку.
+    pub fn reset_max_latency(&mut self) {
+        self.max_latency = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    #[test]
+    fn timed_ring() {
+        let ticks = Cell::new(u64::MAX - 1);
+        let mut ring = FrodoTimedRing::<_, u8, 4>::new(|| ticks.get());
+
+        assert!(ring.push(0x1).is_ok());
+        ticks.set(ticks.get().wrapping_add(3));
+        assert!(ring.push(0x2).is_ok());
+        ticks.set(ticks.get().wrapping_add(2));
+
+        assert_eq!(ring.age(0), Some(5));
+        assert_eq!(ring.age(1), Some(2));
+        assert_eq!(ring.age(2), None);
+
+        assert_eq!(ring.pick(), Some((0x1, 5)));
+        ticks.set(ticks.get().wrapping_add(1));
+        assert_eq!(ring.pick(), Some((0x2, 3)));
+        assert_eq!(ring.max_latency(), 5);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn timed_ring_32bit_wrap() {
+        struct Cyccnt<'a>(&'a Cell<u32>);
+
+        impl TimeSource for Cyccnt<'_> {
+            const WRAP_MASK: u64 = u32::MAX as u64;
+
+            fn now(&self) -> u64 {
+                self.0.get() as u64
+            }
+        }
+
+        let ticks = Cell::new(u32::MAX - 1);
+        let mut ring = FrodoTimedRing::<_, u8, 4>::new(Cyccnt(&ticks));
+
+        assert!(ring.push(0x1).is_ok());
+        ticks.set(ticks.get().wrapping_add(4));
+        assert_eq!(ring.age(0), Some(4));
+        assert_eq!(ring.pick(), Some((0x1, 4)));
+        assert_eq!(ring.max_latency(), 4);
+    }
+}
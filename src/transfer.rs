@@ -0,0 +1,124 @@
+//! Перенос элемента между двумя очередями.
+
+use crate::FrodoRing;
+
+/// Ошибка `transfer()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferError {
+    /// В исходной ячейке нет элемента.
+    Empty,
+    /// В очереди назначения нет свободной ячейки; исходная очередь не изменена.
+    Full,
+}
+
+impl core::fmt::Display for TransferError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "source cell is empty"),
+            Self::Full => write!(f, "destination ring is full"),
+        }
+    }
+}
+
+impl core::error::Error for TransferError {}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Переносит элемент из ячейки по наивной позиции в конец очереди `dst` одной операцией.
+    ///
+    /// Место в `dst` находится до изъятия элемента, поэтому при заполненной `dst` элемент остаётся на месте
+    /// и ни при каком исходе не теряется. Если в `dst` открыта транзакция, элемент откладывается в ней.
+    pub fn transfer<const M: usize>(
+        &mut self,
+        naive_pos: isize,
+        dst: &mut FrodoRing<T, M>,
+    ) -> Result<(), TransferError> {
+        let src = self
+            .checked_real_pos(naive_pos)
+            .filter(|src| self.occupied[*src])
+            .ok_or(TransferError::Empty)?;
+        let dst_pos = dst.free_cell().ok_or(TransferError::Full)?;
+
+        let item = self.take(src);
+        dst.buffer[dst_pos].write(item);
+        dst.fill_cell();
+        Ok(())
+    }
+
+    /// Переносит до `max` элементов, отвечающих условию, в конец очереди `dst`, сохраняя их порядок.
+    ///
+    /// Останавливается, когда перенесено `max` элементов или в `dst` закончилось место; возвращает
+    /// число перенесённых элементов. Неперенесённые элементы остаются на своих местах.
+    pub fn transfer_matching<const M: usize, F: FnMut(&T) -> bool>(
+        &mut self,
+        dst: &mut FrodoRing<T, M>,
+        mut predicate: F,
+        max: usize,
+    ) -> usize {
+        // Изъятие элементов не сдвигает остальные, поэтому обходим реальные позиции исходного участка
+        let (head, cap) = (self.head, self.cap);
+        let mut moved = 0usize;
+
+        for naive_pos in 0..cap {
+            if moved == max {
+                break;
+            }
+
+            let src = (head + naive_pos) % N;
+            if !self.occupied[src] || !predicate(unsafe { self.buffer[src].assume_init_ref() }) {
+                continue;
+            }
+
+            let Some(dst_pos) = dst.free_cell() else {
+                break;
+            };
+            let item = self.take(src);
+            dst.buffer[dst_pos].write(item);
+            dst.fill_cell();
+            moved += 1;
+        }
+
+        moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer() {
+        let mut pending = FrodoRing::<u8, 4>::new();
+        let mut in_flight = FrodoRing::<u8, 2>::new();
+        for i in 1..=4 {
+            pending.push(i).unwrap();
+        }
+
+        assert_eq!(pending.transfer(1, &mut in_flight), Ok(()));
+        assert_eq!(
+            pending.transfer(1, &mut in_flight),
+            Err(TransferError::Empty)
+        );
+        assert_eq!(pending.transfer(-1, &mut in_flight), Ok(()));
+        assert_eq!(
+            pending.transfer(0, &mut in_flight),
+            Err(TransferError::Full)
+        );
+
+        assert_eq!(pending.iter().copied().collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(in_flight.iter().copied().collect::<Vec<_>>(), [2, 4]);
+    }
+
+    #[test]
+    fn transfer_matching() {
+        let mut core0 = FrodoRing::<u8, 8>::new();
+        let mut core1 = FrodoRing::<u8, 3>::new();
+        for i in 1..=8 {
+            core0.push(i).unwrap();
+        }
+
+        assert_eq!(core0.transfer_matching(&mut core1, |el| el % 2 == 1, 2), 2);
+        assert_eq!(core0.transfer_matching(&mut core1, |el| el % 2 == 1, 5), 1);
+        assert_eq!(core1.iter().copied().collect::<Vec<_>>(), [1, 3, 5]);
+        assert_eq!(core0.iter().copied().collect::<Vec<_>>(), [2, 4, 6, 7, 8]);
+    }
+}
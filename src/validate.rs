@@ -0,0 +1,187 @@
+//! Проверка структурных инвариантов очереди.
+
+use crate::FrodoRing;
+
+/// Нарушение структурного инварианта очереди.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Начало очереди указывает за пределы буфера.
+    HeadOutOfBounds { head: usize },
+    /// Используемая ёмкость превышает размер буфера.
+    CapOutOfBounds { cap: usize },
+    /// Первая ячейка непустой очереди не занята и не зарезервирована.
+    HeadEmpty { head: usize },
+    /// Последняя ячейка непустой очереди не занята и не зарезервирована.
+    TailEmpty { tail: usize },
+    /// Занятая ячейка находится за пределами используемой ёмкости.
+    OccupiedOutsideSpan { real_pos: usize },
+    /// Зарезервированная ячейка занята или находится за пределами используемой ёмкости.
+    ReservedOutsideSpan { real_pos: usize },
+    /// Счётчик резервирований не совпадает с числом зарезервированных ячеек.
+    ReservationsMismatch { counted: usize, expected: usize },
+    /// Отложенные элементы числятся без открытой транзакции.
+    StagedWithoutTransaction { staged: usize },
+    /// Отложенные элементы пересекаются с используемой ёмкостью или не помещаются в буфер.
+    StagingOverlap { stage_head: usize, staged: usize },
+}
+
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::HeadOutOfBounds { head } => write!(f, "head {head} is out of buffer bounds"),
+            Self::CapOutOfBounds { cap } => write!(f, "used capacity {cap} exceeds buffer size"),
+            Self::HeadEmpty { head } => {
+                write!(f, "head cell {head} is neither occupied nor reserved")
+            }
+            Self::TailEmpty { tail } => {
+                write!(f, "tail cell {tail} is neither occupied nor reserved")
+            }
+            Self::OccupiedOutsideSpan { real_pos } => {
+                write!(f, "occupied cell {real_pos} is outside the used span")
+            }
+            Self::ReservedOutsideSpan { real_pos } => {
+                write!(
+                    f,
+                    "reserved cell {real_pos} is occupied or outside the used span"
+                )
+            }
+            Self::ReservationsMismatch { counted, expected } => {
+                write!(
+                    f,
+                    "{counted} reserved cells found, {expected} reservations recorded"
+                )
+            }
+            Self::StagedWithoutTransaction { staged } => {
+                write!(f, "{staged} staged elements without an open transaction")
+            }
+            Self::StagingOverlap { stage_head, staged } => {
+                write!(
+                    f,
+                    "{staged} staged elements at {stage_head} overlap the used span"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Проверяет все структурные инварианты очереди.
+    pub(crate) fn check_invariants(&self) -> Result<(), ValidationError> {
+        if self.head >= N {
+            return Err(ValidationError::HeadOutOfBounds { head: self.head });
+        }
+        if self.cap > N {
+            return Err(ValidationError::CapOutOfBounds { cap: self.cap });
+        }
+
+        if self.cap > 0 {
+            if !self.occupied[self.head] && !self.reserved[self.head] {
+                return Err(ValidationError::HeadEmpty { head: self.head });
+            }
+            let tail = self.real_pos(self.cap - 1);
+            if !self.occupied[tail] && !self.reserved[tail] {
+                return Err(ValidationError::TailEmpty { tail });
+            }
+        }
+
+        for real_pos in 0..N {
+            let inside = (real_pos + N - self.head) % N < self.cap;
+            if self.occupied[real_pos] && !inside {
+                return Err(ValidationError::OccupiedOutsideSpan { real_pos });
+            }
+            if self.reserved[real_pos] && (!inside || self.occupied[real_pos]) {
+                return Err(ValidationError::ReservedOutsideSpan { real_pos });
+            }
+        }
+
+        let expected = self.reserved.iter().filter(|r| **r).count();
+        if expected != self.reservations {
+            return Err(ValidationError::ReservationsMismatch {
+                counted: self.reservations,
+                expected,
+            });
+        }
+
+        match self.staging {
+            None if self.staged > 0 => {
+                return Err(ValidationError::StagedWithoutTransaction {
+                    staged: self.staged,
+                });
+            }
+            Some(stage_head) => {
+                let span = if self.cap == 0 {
+                    self.staged
+                } else {
+                    self.stage_offset(stage_head) + self.staged
+                };
+                if stage_head >= N
+                    || span > N
+                    || (self.cap > 0 && self.stage_offset(stage_head) < self.cap)
+                {
+                    return Err(ValidationError::StagingOverlap {
+                        stage_head,
+                        staged: self.staged,
+                    });
+                }
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    /// Проверяет структурные инварианты очереди и сообщает о первом найденном нарушении.
+    ///
+    /// Полезно при оборачивании очереди в собственные политики. Доступно в тестах и с фичей `validate`.
+    #[cfg(any(test, feature = "validate"))]
+    pub fn debug_validate(&self) -> Result<(), ValidationError> {
+        self.check_invariants()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        assert_eq!(ring.debug_validate(), Ok(()));
+
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x2));
+        assert_eq!(ring.debug_validate(), Ok(()));
+
+        assert_eq!(ring.remove_at(-1), Some(0x3));
+        assert_eq!(ring.used(), 1);
+        assert_eq!(ring.debug_validate(), Ok(()));
+
+        let slot = ring.reserve().unwrap();
+        assert_eq!(ring.debug_validate(), Ok(()));
+        ring.abort_slot(slot);
+
+        ring.begin();
+        assert!(ring.push(0x4).is_ok());
+        assert_eq!(ring.debug_validate(), Ok(()));
+        ring.commit();
+        assert_eq!(ring.debug_validate(), Ok(()));
+
+        ring.occupied[ring.real_pos(ring.cap - 1)] = false;
+        assert_eq!(
+            ring.debug_validate(),
+            Err(ValidationError::TailEmpty { tail: 1 })
+        );
+        ring.occupied[1] = true;
+
+        ring.occupied[3] = true;
+        ring.buffer[3].write(0x5);
+        assert_eq!(
+            ring.debug_validate(),
+            Err(ValidationError::OccupiedOutsideSpan { real_pos: 3 })
+        );
+    }
+}
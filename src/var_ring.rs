@@ -0,0 +1,303 @@
+//! Очередь записей переменной длины в одном байтовом буфере.
+
+/// Размер заголовка записи: длина (`u16`, LE), состояние, резервный байт и поколение (`u32`, LE).
+const HEADER: usize = 8;
+
+/// Запись присутствует в очереди.
+const LIVE: u8 = 0;
+/// Запись отменена и будет освобождена, когда начало очереди дойдёт до неё.
+const CANCELLED: u8 = 1;
+/// Заполнитель до конца буфера: следующая запись начинается с нулевого смещения.
+const PADDING: u8 = 2;
+
+/// Дескриптор записи `FrodoVarRing`, выданный `push()`.
+///
+/// Хранит смещение записи и её поколение, так что дескриптор уже изъятой записи не совпадёт с новой записью в том же месте.
+/// Поколение 32-битное: дескриптор спутается с новой записью, лишь если между ними положено ровно `2^32·k` записей.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrodoRecord {
+    offset: usize,
+    generation: u32,
+}
+
+/// Очередь FIFO записей разной длины, хранящихся подряд в буфере на `N` байт.
+///
+/// Каждая запись занимает заголовок на 8 байт и свои данные; запись никогда не разрывается концом буфера.
+/// Как и `FrodoRing`, очередь позволяет отменять записи из середины (`cancel()`), оставляя на их месте пропуски,
+/// которые освобождаются, когда начало очереди доходит до них.
+pub struct FrodoVarRing<const N: usize> {
+    buffer: [u8; N],
+    /// Смещение первой записи.
+    head: usize,
+    /// Смещение, с которого будет записана следующая запись.
+    tail: usize,
+    /// Число занятых байт, включая заголовки, отменённые записи и заполнители.
+    used: usize,
+    /// Поколение следующей записи.
+    generation: u32,
+}
+
+impl<const N: usize> Default for FrodoVarRing<N> {
+    fn default() -> Self {
+        Self {
+            buffer: [0; N],
+            head: 0,
+            tail: 0,
+            used: 0,
+            generation: 0,
+        }
+    }
+}
+
+impl<const N: usize> FrodoVarRing<N> {
+    /// Максимальная длина данных одной записи.
+    pub const MAX_RECORD: usize = if N > HEADER + u16::MAX as usize {
+        u16::MAX as usize
+    } else {
+        N.saturating_sub(HEADER)
+    };
+
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает число занятых байт, включая заголовки и пропуски.
+    pub fn used_bytes(&self) -> usize {
+        self.used
+    }
+
+    /// Возвращает число присутствующих в очереди записей.
+    pub fn len(&self) -> usize {
+        self.records()
+            .filter(|(_, state, _)| *state == LIVE)
+            .count()
+    }
+
+    /// Сообщает, есть ли в очереди записи.
+    pub fn is_empty(&self) -> bool {
+        self.used == 0
+    }
+
+    /// Кладёт запись в очередь, возвращая её дескриптор.
+    ///
+    /// Возвращает `None`, если запись длиннее `Self::MAX_RECORD` или для неё нет непрерывного свободного места.
+    pub fn push(&mut self, data: &[u8]) -> Option<FrodoRecord> {
+        if data.len() > Self::MAX_RECORD {
+            return None;
+        }
+        if self.used == 0 {
+            self.head = 0;
+            self.tail = 0;
+        }
+
+        let size = HEADER + data.len();
+        let wrapped = self.used > 0 && self.tail <= self.head;
+
+        let offset = if wrapped {
+            if self.head - self.tail < size {
+                return None;
+            }
+            self.tail
+        } else if N - self.tail >= size {
+            self.tail
+        } else if self.head >= size {
+            // Остаток буфера отдаётся под заполнитель, запись начинается с нуля
+            if N - self.tail >= HEADER {
+                self.buffer[self.tail + 2] = PADDING;
+            }
+            self.used += N - self.tail;
+            0
+        } else {
+            return None;
+        };
+
+        let generation = self.generation;
+        self.generation = self.generation.wrapping_add(1);
+
+        self.buffer[offset..offset + 2].copy_from_slice(&(data.len() as u16).to_le_bytes());
+        self.buffer[offset + 2] = LIVE;
+        self.buffer[offset + 3] = 0;
+        self.buffer[offset + 4..offset + HEADER].copy_from_slice(&generation.to_le_bytes());
+        self.buffer[offset + HEADER..offset + size].copy_from_slice(data);
+
+        self.tail = (offset + size) % N;
+        self.used += size;
+        Some(FrodoRecord { offset, generation })
+    }
+
+    /// Возвращает данные первой записи, не изымая её.
+    pub fn peek(&self) -> Option<&[u8]> {
+        self.iter().next()
+    }
+
+    /// Изымает первую запись, копируя её данные в `out`, и возвращает их длину.
+    ///
+    /// Если `out` короче записи, запись остаётся в очереди и возвращается `None`.
+    pub fn pick(&mut self, out: &mut [u8]) -> Option<usize> {
+        let data = self.peek()?;
+        let len = data.len();
+        out.get_mut(..len)?.copy_from_slice(data);
+        self.discard();
+        Some(len)
+    }
+
+    /// Удаляет первую запись без копирования. Возвращает `false`, если очередь пуста.
+    pub fn discard(&mut self) -> bool {
+        let Some((offset, _, _)) = self.records().next() else {
+            return false;
+        };
+
+        self.buffer[offset + 2] = CANCELLED;
+        self.trim();
+        true
+    }
+
+    /// Получает данные записи по дескриптору, если она ещё в очереди.
+    pub fn get(&self, record: FrodoRecord) -> Option<&[u8]> {
+        let (offset, _, len) = self.find(record)?;
+        Some(&self.buffer[offset + HEADER..offset + HEADER + len])
+    }
+
+    /// Отменяет запись по дескриптору, оставляя на её месте пропуск. Возвращает `false`, если записи уже нет.
+    pub fn cancel(&mut self, record: FrodoRecord) -> bool {
+        let Some((offset, _, _)) = self.find(record) else {
+            return false;
+        };
+
+        self.buffer[offset + 2] = CANCELLED;
+        self.trim();
+        true
+    }
+
+    /// Создаёт итератор по данным присутствующих записей в порядке очереди.
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.records()
+            .filter(|(_, state, _)| *state == LIVE)
+            .map(|(offset, _, len)| &self.buffer[offset + HEADER..offset + HEADER + len])
+    }
+
+    fn find(&self, record: FrodoRecord) -> Option<(usize, u8, usize)> {
+        self.records().find(|(offset, state, _)| {
+            *offset == record.offset
+                && *state == LIVE
+                && self.buffer[offset + 4..offset + HEADER] == record.generation.to_le_bytes()
+        })
+    }
+
+    /// Обходит записи (включая отменённые) от начала очереди: смещение, состояние, длина данных.
+    fn records(&self) -> impl Iterator<Item = (usize, u8, usize)> + '_ {
+        let mut pos = self.head;
+        let mut remaining = self.used;
+
+        core::iter::from_fn(move || {
+            loop {
+                if remaining == 0 {
+                    return None;
+                }
+                if N - pos < HEADER || self.buffer[pos + 2] == PADDING {
+                    remaining -= N - pos;
+                    pos = 0;
+                    continue;
+                }
+
+                let len = u16::from_le_bytes([self.buffer[pos], self.buffer[pos + 1]]) as usize;
+                let record = (pos, self.buffer[pos + 2], len);
+                remaining -= HEADER + len;
+                pos = (pos + HEADER + len) % N;
+                return Some(record);
+            }
+        })
+    }
+
+    /// Освобождает отменённые записи и заполнители в начале очереди.
+    fn trim(&mut self) {
+        while self.used > 0 {
+            if N - self.head < HEADER || self.buffer[self.head + 2] == PADDING {
+                self.used -= N - self.head;
+                self.head = 0;
+                continue;
+            }
+            if self.buffer[self.head + 2] != CANCELLED {
+                break;
+            }
+
+            let len =
+                u16::from_le_bytes([self.buffer[self.head], self.buffer[self.head + 1]]) as usize;
+            self.used -= HEADER + len;
+            self.head = (self.head + HEADER + len) % N;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn var_ring() {
+        let mut ring = FrodoVarRing::<48>::new();
+        let mut out = [0u8; 16];
+
+        let a = ring.push(b"hello").unwrap();
+        let b = ring.push(b"wide world").unwrap();
+        let c = ring.push(b"!").unwrap();
+        assert_eq!(ring.used_bytes(), 13 + 18 + 9);
+        assert!(ring.push(b"xyz").is_none());
+
+        assert_eq!(ring.get(b), Some(&b"wide world"[..]));
+        assert!(ring.cancel(b));
+        assert!(!ring.cancel(b));
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.iter().collect::<Vec<_>>(), [&b"hello"[..], &b"!"[..]]);
+
+        assert_eq!(ring.pick(&mut out[..2]), None);
+        assert_eq!(ring.pick(&mut out), Some(5));
+        assert_eq!(&out[..5], b"hello");
+        assert_eq!(ring.get(a), None);
+        assert_eq!(ring.used_bytes(), 9);
+
+        // Не помещается в конец буфера - переносится в начало
+        let d = ring.push(b"wrapped").unwrap();
+        assert_eq!(d.offset, 0);
+        assert_eq!(
+            ring.iter().collect::<Vec<_>>(),
+            [&b"!"[..], &b"wrapped"[..]]
+        );
+
+        assert_eq!(ring.peek(), Some(&b"!"[..]));
+        assert!(ring.cancel(c));
+        assert_eq!(ring.peek(), Some(&b"wrapped"[..]));
+        assert_eq!(ring.used_bytes(), 15);
+        assert!(ring.discard());
+        assert!(ring.is_empty());
+        assert!(!ring.discard());
+    }
+
+    #[test]
+    fn var_ring_too_long() {
+        let mut ring = FrodoVarRing::<12>::new();
+        assert_eq!(FrodoVarRing::<12>::MAX_RECORD, 4);
+        assert!(ring.push(b"12345").is_none());
+        assert!(ring.push(b"1234").is_some());
+        assert!(ring.push(b"").is_none());
+    }
+
+    #[test]
+    fn var_ring_stale_record() {
+        let mut ring = FrodoVarRing::<16>::new();
+        let stale = ring.push(b"old").unwrap();
+        assert!(ring.discard());
+
+        for _ in 0..0x1FF {
+            ring.push(b"mid").unwrap();
+            assert!(ring.discard());
+        }
+
+        let fresh = ring.push(b"new").unwrap();
+        assert_eq!(fresh.offset, stale.offset);
+        assert_eq!(ring.get(stale), None);
+        assert!(!ring.cancel(stale));
+        assert_eq!(ring.get(fresh), Some(&b"new"[..]));
+    }
+}
@@ -0,0 +1,61 @@
+//! Преобразования между `FrodoRing` и `VecDeque` (при включённой функции `std`).
+
+use std::collections::VecDeque;
+
+use crate::FrodoRing;
+
+impl<T, const N: usize> From<FrodoRing<T, N>> for VecDeque<T> {
+    /// Переносит элементы очереди в `VecDeque`, сохраняя порядок; пропуски не переносятся.
+    fn from(mut ring: FrodoRing<T, N>) -> Self {
+        let mut deque = VecDeque::with_capacity(ring.len());
+        while let Some(item) = ring.pick() {
+            deque.push_back(item);
+        }
+        deque
+    }
+}
+
+impl<T, const N: usize> TryFrom<VecDeque<T>> for FrodoRing<T, N> {
+    type Error = VecDeque<T>;
+
+    /// Переносит элементы `VecDeque` в новую очередь; если они не помещаются, возвращает `VecDeque` нетронутым.
+    fn try_from(mut deque: VecDeque<T>) -> Result<Self, Self::Error> {
+        if deque.len() > N {
+            return Err(deque);
+        }
+
+        let mut ring = Self::new();
+        for item in deque.drain(..) {
+            let _ = ring.push(item);
+        }
+        Ok(ring)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use crate::FrodoRing;
+
+    #[test]
+    fn vec_deque_roundtrip() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        for i in 0..4 {
+            ring.push(i).unwrap();
+        }
+        ring.remove_at(1);
+
+        let deque = VecDeque::from(ring);
+        assert_eq!(deque, [0, 2, 3]);
+
+        let ring = FrodoRing::<u8, 3>::try_from(deque).unwrap();
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [0, 2, 3]);
+
+        let too_long = VecDeque::from([1, 2, 3, 4]);
+        assert_eq!(
+            FrodoRing::<u8, 3>::try_from(too_long).unwrap_err(),
+            [1, 2, 3, 4]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Неизменяемое представление очереди для передачи в другие подсистемы.
+
+use crate::{FrodoRing, FrodoRingIterator};
+
+/// Представление очереди, предоставляющее только API чтения.
+///
+/// Дёшево копируется; `Send` и `Sync` при `T: Sync`, поэтому его можно передать, например, потоку логирования.
+pub struct FrodoRingView<'ring, T, const N: usize> {
+    ring: &'ring FrodoRing<T, N>,
+}
+
+impl<T, const N: usize> Clone for FrodoRingView<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize> Copy for FrodoRingView<'_, T, N> {}
+
+impl<'ring, T, const N: usize> From<&'ring FrodoRing<T, N>> for FrodoRingView<'ring, T, N> {
+    fn from(ring: &'ring FrodoRing<T, N>) -> Self {
+        Self { ring }
+    }
+}
+
+impl<'ring, T, const N: usize> FrodoRingView<'ring, T, N> {
+    /// Возвращает использованное число ячеек кольцевой очереди.
+    pub fn used(&self) -> usize {
+        self.ring.used()
+    }
+
+    /// Возвращает число элементов, находящихся в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди элементы.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Получает элемент по ячейке (наивной позиции); см. `FrodoRing::at`.
+    pub fn at(&self, naive_pos: isize) -> Option<&'ring T> {
+        self.ring.at(naive_pos)
+    }
+
+    /// Получает элемент по очереди; см. `FrodoRing::get`.
+    pub fn get(&self, pos: usize) -> Option<&'ring T> {
+        self.ring.get(pos)
+    }
+
+    /// Создаёт итератор по очереди.
+    pub fn iter(&self) -> FrodoRingIterator<'ring, T, N> {
+        self.ring.iter()
+    }
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Возвращает неизменяемое представление очереди.
+    pub fn view(&self) -> FrodoRingView<'_, T, N> {
+        FrodoRingView::from(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<V: Send + Sync>(_: &V) {}
+
+    #[test]
+    fn view() {
+        let mut ring = FrodoRing::<u8, 4>::new();
+        assert!(ring.push(0x1).is_ok());
+        assert!(ring.push(0x2).is_ok());
+        assert!(ring.push(0x3).is_ok());
+        assert_eq!(ring.remove_at(1), Some(0x2));
+
+        let view = ring.view();
+        assert_send_sync(&view);
+
+        let copy = view;
+        assert_eq!(view.len(), 2);
+        assert_eq!(copy.used(), 3);
+        assert_eq!(view.at(1), None);
+        assert_eq!(view.get(1), Some(&0x3));
+
+        let seen = std::thread::scope(|s| s.spawn(move || copy.iter().sum::<u8>()).join().unwrap());
+        assert_eq!(seen, 0x4);
+    }
+}
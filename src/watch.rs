@@ -0,0 +1,98 @@
+//! Очередь "последнего значения": новое значение по ключу замещает прежнее.
+
+use core::mem;
+
+use crate::FrodoRing;
+
+/// Очередь пар ключ-значение, в которой для каждого ключа хранится только последнее значение.
+///
+/// Значение для уже присутствующего ключа заменяется на месте, сохраняя место ключа в очереди;
+/// ключи выдаются в порядке их первого появления. Подходит для случаев, когда важно лишь последнее
+/// показание каждого датчика.
+pub struct FrodoWatch<K, V, const N: usize> {
+    ring: FrodoRing<(K, V), N>,
+}
+
+impl<K, V, const N: usize> Default for FrodoWatch<K, V, N> {
+    fn default() -> Self {
+        Self {
+            ring: FrodoRing::new(),
+        }
+    }
+}
+
+impl<K: PartialEq, V, const N: usize> FrodoWatch<K, V, N> {
+    /// Создаёт новую очередь.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Находит реальную позицию ячейки с ключом `key`.
+    fn real_pos(&self, key: &K) -> Option<usize> {
+        let naive_pos = self.ring.position(|(k, _)| k == key)?;
+        self.ring.checked_real_pos(naive_pos)
+    }
+
+    /// Возвращает число ключей в очереди.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Сообщает, есть ли в очереди ключи.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Кладёт значение для ключа.
+    ///
+    /// Если ключ уже в очереди, значение заменяется на месте и возвращается прежнее значение.
+    /// Если ключа нет и места тоже нет, пара возвращается обратно.
+    pub fn push(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        if let Some(real_pos) = self.real_pos(&key) {
+            let (_, current) = unsafe { self.ring.buffer[real_pos].assume_init_mut() };
+            return Ok(Some(mem::replace(current, value)));
+        }
+
+        self.ring.push((key, value)).map(|()| None)
+    }
+
+    /// Получает последнее значение для ключа.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let real_pos = self.real_pos(key)?;
+        let (_, value) = unsafe { self.ring.buffer[real_pos].assume_init_ref() };
+        Some(value)
+    }
+
+    /// Изымает ключ, появившийся раньше остальных, вместе с его последним значением.
+    pub fn pick(&mut self) -> Option<(K, V)> {
+        self.ring.pick()
+    }
+
+    /// Создаёт итератор по парам в порядке первого появления ключей.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.ring.iter().map(|(key, value)| (key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watch_latest_value() {
+        let mut watch = FrodoWatch::<&str, i16, 2>::new();
+        assert_eq!(watch.push("temp", 20), Ok(None));
+        assert_eq!(watch.push("hum", 40), Ok(None));
+        assert_eq!(watch.push("temp", 21), Ok(Some(20)));
+        assert_eq!(watch.push("press", 1013), Err(("press", 1013)));
+
+        assert_eq!(watch.get(&"temp"), Some(&21));
+        assert_eq!(watch.len(), 2);
+        assert_eq!(watch.pick(), Some(("temp", 21)));
+        assert_eq!(watch.push("press", 1013), Ok(None));
+        assert_eq!(
+            watch.iter().collect::<Vec<_>>(),
+            [(&"hum", &40), (&"press", &1013)]
+        );
+    }
+}
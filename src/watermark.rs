@@ -0,0 +1,113 @@
+//! Пороги заполненности очереди для управления потоком.
+
+use crate::FrodoRing;
+
+/// Пересечённый порог заполненности.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Watermark {
+    /// Число элементов достигло верхнего порога: производителя стоит притормозить.
+    High,
+    /// Число элементов опустилось до нижнего порога: производитель может продолжать.
+    Low,
+}
+
+/// Настроенные пороги и текущее состояние.
+pub(crate) struct Watermarks {
+    low: usize,
+    high: usize,
+    callback: fn(Watermark),
+    above: bool,
+}
+
+impl<T, const N: usize> FrodoRing<T, N> {
+    /// Задаёт пороги заполненности: при достижении `high` элементов вызывается `callback(Watermark::High)`,
+    /// а после этого при снижении до `low` - `callback(Watermark::Low)`.
+    ///
+    /// Между порогами действует гистерезис, так что обработчик не вызывается на каждом элементе.
+    /// Обработчик может выставить флаг или разбудить задачу, например снять сигнал RTS.
+    /// Возвращает `false`, если `low >= high` или `high > N`.
+    pub fn set_watermarks(&mut self, low: usize, high: usize, callback: fn(Watermark)) -> bool {
+        if low >= high || high > N {
+            return false;
+        }
+
+        self.watermarks = Some(Watermarks {
+            low,
+            high,
+            callback,
+            above: false,
+        });
+        self.check_watermarks();
+        true
+    }
+
+    /// Снимает пороги заполненности.
+    pub fn clear_watermarks(&mut self) {
+        self.watermarks = None;
+    }
+
+    /// Сообщает, что верхний порог достигнут и ещё не был сброшен нижним.
+    pub fn above_high_watermark(&self) -> bool {
+        self.watermarks.as_ref().is_some_and(|w| w.above)
+    }
+
+    /// Проверяет пересечение порогов после изменения числа элементов.
+    pub(crate) fn check_watermarks(&mut self) {
+        if self.watermarks.is_none() {
+            return;
+        }
+
+        let len = self.len();
+        let Some(watermarks) = self.watermarks.as_mut() else {
+            return;
+        };
+
+        if !watermarks.above && len >= watermarks.high {
+            watermarks.above = true;
+            (watermarks.callback)(Watermark::High);
+        } else if watermarks.above && len <= watermarks.low {
+            watermarks.above = false;
+            (watermarks.callback)(Watermark::Low);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use super::*;
+
+    static RTS: AtomicBool = AtomicBool::new(true);
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn on_watermark(watermark: Watermark) {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        RTS.store(watermark == Watermark::Low, Ordering::Relaxed);
+    }
+
+    #[test]
+    fn watermarks() {
+        let mut ring = FrodoRing::<u8, 8>::new();
+        assert!(!ring.set_watermarks(4, 4, on_watermark));
+        assert!(ring.set_watermarks(2, 6, on_watermark));
+
+        for i in 0..6 {
+            ring.push(i).unwrap();
+        }
+        assert!(!RTS.load(Ordering::Relaxed));
+        assert!(ring.above_high_watermark());
+
+        ring.push(6).unwrap();
+        ring.remove_at(1);
+        ring.pick();
+        ring.pick();
+        assert!(!RTS.load(Ordering::Relaxed));
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+
+        ring.remove_range(..2);
+        assert!(RTS.load(Ordering::Relaxed));
+        assert!(!ring.above_high_watermark());
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,111 @@
+//! Скользящее окно с минимумом и максимумом за `O(1)`.
+
+use crate::FrodoRing;
+
+/// Окно последних `N` отсчётов, поддерживающее текущие минимум и максимум.
+///
+/// Рядом с отсчётами хранятся две монотонные очереди с их номерами: при добавлении отсчёта с конца выбрасываются
+/// кандидаты, которые уже никогда не станут экстремумом, а при выходе отсчёта из окна он снимается с начала.
+/// Так `max()` и `min()` занимают `O(1)`, а `push()` - `O(1)` в среднем.
+pub struct FrodoWindow<T, const N: usize> {
+    samples: FrodoRing<T, N>,
+    /// Кандидаты в максимумы по убыванию значения.
+    max: FrodoRing<(u64, T), N>,
+    /// Кандидаты в минимумы по возрастанию значения.
+    min: FrodoRing<(u64, T), N>,
+    next_seq: u64,
+}
+
+impl<T, const N: usize> Default for FrodoWindow<T, N> {
+    fn default() -> Self {
+        Self {
+            samples: FrodoRing::new(),
+            max: FrodoRing::new(),
+            min: FrodoRing::new(),
+            next_seq: 0,
+        }
+    }
+}
+
+impl<T: Copy + PartialOrd, const N: usize> FrodoWindow<T, N> {
+    /// Создаёт пустое окно.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает отсчёты окна от старого к новому.
+    pub fn samples(&self) -> &FrodoRing<T, N> {
+        &self.samples
+    }
+
+    /// Возвращает число отсчётов в окне.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Сообщает, есть ли в окне отсчёты.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Добавляет отсчёт; если окно заполнено, старейший отсчёт выходит из окна и возвращается.
+    pub fn push(&mut self, sample: T) -> Option<T> {
+        let expired = if self.samples.len() == N {
+            self.expire()
+        } else {
+            None
+        };
+
+        while self.max.pop_back_if(|(_, max)| *max <= sample).is_some() {}
+        while self.min.pop_back_if(|(_, min)| *min >= sample).is_some() {}
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let _ = self.samples.push(sample);
+        let _ = self.max.push((seq, sample));
+        let _ = self.min.push((seq, sample));
+        expired
+    }
+
+    /// Выводит из окна старейший отсчёт и возвращает его.
+    pub fn expire(&mut self) -> Option<T> {
+        let sample = self.samples.pick()?;
+        let seq = self.next_seq - self.samples.len() as u64 - 1;
+        self.max.peek_then_remove(|(s, _)| *s == seq);
+        self.min.peek_then_remove(|(s, _)| *s == seq);
+        Some(sample)
+    }
+
+    /// Возвращает наибольший отсчёт в окне.
+    pub fn max(&self) -> Option<T> {
+        self.max.at(0).map(|(_, max)| *max)
+    }
+
+    /// Возвращает наименьший отсчёт в окне.
+    pub fn min(&self) -> Option<T> {
+        self.min.at(0).map(|(_, min)| *min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_extremes() {
+        let mut window = FrodoWindow::<i32, 3>::new();
+        assert_eq!(window.max(), None);
+
+        let samples = [5, 1, 3, 3, 7, 2, 2, -4, 6];
+        for (i, sample) in samples.iter().enumerate() {
+            window.push(*sample);
+            let span = &samples[i.saturating_sub(2)..=i];
+            assert_eq!(window.max(), span.iter().max().copied());
+            assert_eq!(window.min(), span.iter().min().copied());
+        }
+
+        assert_eq!(window.expire(), Some(2));
+        assert_eq!(window.max(), Some(6));
+        assert_eq!(window.min(), Some(-4));
+    }
+}